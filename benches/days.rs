@@ -0,0 +1,54 @@
+//! Time parsing and both parts of every registered day
+//!
+//! Every day is measured against its example input, `inputs/dayNN.example.txt`,
+//! and against the real input `inputs/dayNN.txt` when it's available locally.
+//!
+//! Criterion keeps baselines to compare an optimisation against:
+//!
+//! ```text
+//! cargo bench --bench days -- --save-baseline before
+//! # change some code
+//! cargo bench --bench days -- --baseline before
+//! ```
+//!
+//! Filter by the benchmark name to only run some days, e.g. `-- day06/real`.
+use aoc_rs_2024::{
+    inputs::{InputKind, InputStore},
+    solution::{AnySolution, Part, SOLUTIONS},
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn bench_input(c: &mut Criterion, solution: &dyn AnySolution, name: &str, input: &str) {
+    let day = solution.day();
+    let parsed = match solution.parse(input) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("skipping day {day} {name} input: {e}");
+            return;
+        }
+    };
+
+    let mut group = c.benchmark_group(format!("day{day:02}/{name}"));
+    group.bench_function("parse", |b| b.iter(|| solution.parse(input)));
+    for part in Part::ALL {
+        group.bench_function(format!("part{part}"), |b| b.iter(|| parsed.solve(part)));
+    }
+    group.finish();
+}
+
+fn bench_days(c: &mut Criterion) {
+    let store = InputStore::new(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs"));
+    for solution in SOLUTIONS {
+        let inputs = [("example", InputKind::Example), ("real", InputKind::Real)];
+        for (name, kind) in inputs {
+            // real inputs are personal and not checked in, so they're optional
+            match store.read(solution.day(), kind) {
+                Ok(input) => bench_input(c, *solution, name, &input),
+                Err(e) => eprintln!("skipping day {} {name} input: {e}", solution.day()),
+            }
+        }
+    }
+}
+
+criterion_group!(benches, bench_days);
+criterion_main!(benches);
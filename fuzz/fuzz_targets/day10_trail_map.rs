@@ -0,0 +1,11 @@
+//! Parse arbitrary text as a topographic map
+//!
+//! `cargo +nightly fuzz run day10_trail_map`, add a test to the day for every crash it finds
+#![no_main]
+
+use aoc_rs_2024::day10::TrailMap;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<TrailMap>();
+});
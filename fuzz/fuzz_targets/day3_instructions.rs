@@ -0,0 +1,16 @@
+//! Scan arbitrary text for instructions, with and without `do()` and `don't()`
+//!
+//! `cargo +nightly fuzz run day3_instructions`, add a test to the day for every crash it finds
+#![no_main]
+
+use aoc_rs_2024::day3::InstructionParser;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for parse_enabled in [false, true] {
+        let mut parser = InstructionParser::new(input, parse_enabled);
+        while parser.next().is_some() {
+            parser.line();
+        }
+    }
+});
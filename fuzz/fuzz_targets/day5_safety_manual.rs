@@ -0,0 +1,11 @@
+//! Parse arbitrary text as ordering rules and page updates
+//!
+//! `cargo +nightly fuzz run day5_safety_manual`, add a test to the day for every crash it finds
+#![no_main]
+
+use aoc_rs_2024::day5::SafetyManual;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<SafetyManual>();
+});
@@ -0,0 +1,11 @@
+//! Parse arbitrary text as the map of the lab
+//!
+//! `cargo +nightly fuzz run day6_grid`, add a test to the day for every crash it finds
+#![no_main]
+
+use aoc_rs_2024::day6::Grid;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<Grid>();
+});
@@ -0,0 +1,11 @@
+//! Parse arbitrary text as a single calibration equation
+//!
+//! `cargo +nightly fuzz run day7_equation`, add a test to the day for every crash it finds
+#![no_main]
+
+use aoc_rs_2024::day7::Equation;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<Equation>();
+});
@@ -0,0 +1,16 @@
+//! Expand, move and solve the files of any disk map the parser accepts
+//!
+//! `cargo +nightly fuzz run day9_memory_map`, add a test to the day for every crash it finds
+#![no_main]
+
+use aoc_rs_2024::day9::{self, MemoryMap, MemoryMapOptimized};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(packed) = day9::parse(input) {
+        MemoryMap::from_packed(&packed);
+        MemoryMapOptimized::from_packed(&packed).move_files();
+        let _ = day9::part1(&packed);
+        let _ = day9::part2(&packed);
+    }
+});
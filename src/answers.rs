@@ -0,0 +1,222 @@
+//! Accepted answers stored in a local toml file, used to catch regressions
+//!
+//! The file has a table per day with the accepted answer of each part:
+//!
+//! ```toml
+//! [day06]
+//! part1 = "41"
+//! part2 = "6"
+//! ```
+use std::{collections::BTreeMap, fmt, fs, io, path::Path};
+
+use toml::{Table, Value};
+
+use crate::solution::{Answer, Part};
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswersError::Io(e) => write!(f, "{e}"),
+            AnswersError::Toml(e) => write!(f, "{e}"),
+            AnswersError::Invalid(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl From<io::Error> for AnswersError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<toml::de::Error> for AnswersError {
+    fn from(value: toml::de::Error) -> Self {
+        Self::Toml(value)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    days: BTreeMap<(u8, Part), Answer>,
+}
+
+fn day_key(day: u8) -> String {
+    format!("day{day:02}")
+}
+
+fn part_key(part: Part) -> String {
+    format!("part{part}")
+}
+
+impl Answers {
+    /// Load the answers from a file, a missing file has no answers yet
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        match fs::read_to_string(path) {
+            Ok(content) => content.parse(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AnswersError> {
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&Answer> {
+        self.days.get(&(day, part))
+    }
+
+    pub fn set(&mut self, day: u8, part: Part, answer: Answer) {
+        self.days.insert((day, part), answer);
+    }
+}
+
+impl std::str::FromStr for Answers {
+    type Err = AnswersError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table: Table = s.parse()?;
+        let mut answers = Self::default();
+        for (day_name, parts) in table {
+            let day = day_name
+                .strip_prefix("day")
+                .and_then(|day| day.parse().ok())
+                .ok_or_else(|| AnswersError::Invalid(format!("invalid day `{day_name}`")))?;
+            let Value::Table(parts) = parts else {
+                return Err(AnswersError::Invalid(format!(
+                    "`{day_name}` must be a table"
+                )));
+            };
+
+            for (part_name, answer) in parts {
+                let part = part_name
+                    .strip_prefix("part")
+                    .and_then(|part| part.parse::<u8>().ok())
+                    .and_then(|part| Part::try_from(part).ok())
+                    .ok_or_else(|| {
+                        AnswersError::Invalid(format!("invalid part `{day_name}.{part_name}`"))
+                    })?;
+                // answers are written as strings, but allow small numbers without quotes
+                let answer = match answer {
+                    Value::String(answer) => Answer::from(answer.as_str()),
+                    Value::Integer(answer) => Answer::from(answer),
+                    _ => {
+                        return Err(AnswersError::Invalid(format!(
+                            "`{day_name}.{part_name}` must be a string or an integer"
+                        )))
+                    }
+                };
+                answers.set(day, part, answer);
+            }
+        }
+
+        Ok(answers)
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut table = Table::new();
+        for ((day, part), answer) in &self.days {
+            let Value::Table(parts) = table
+                .entry(day_key(*day))
+                .or_insert_with(|| Value::Table(Table::new()))
+            else {
+                unreachable!("days are always tables");
+            };
+            parts.insert(part_key(*part), Value::String(answer.to_string()));
+        }
+
+        write!(f, "{table}")
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer },
+    Missing,
+}
+
+impl Verdict {
+    pub fn new(expected: Option<&Answer>, actual: &Answer) -> Self {
+        match expected {
+            Some(expected) if expected == actual => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+            None => Verdict::Missing,
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail { .. } => write!(f, "FAIL"),
+            Verdict::Missing => write!(f, "MISSING"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = r#"[day01]
+part1 = "11"
+part2 = 31
+
+[day06]
+part2 = "6"
+"#;
+
+    #[test]
+    fn test_parse() {
+        let answers: Answers = ANSWERS.parse().unwrap();
+        assert_eq!(answers.get(1, Part::One), Some(&Answer::Integer(11)));
+        assert_eq!(answers.get(1, Part::Two), Some(&Answer::Integer(31)));
+        assert_eq!(answers.get(6, Part::One), None);
+        assert_eq!(answers.get(6, Part::Two), Some(&Answer::Integer(6)));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let answers: Answers = ANSWERS.parse().unwrap();
+        let reparsed: Answers = answers.to_string().parse().unwrap();
+        assert_eq!(answers, reparsed);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!("[day1x]\npart1 = \"1\"".parse::<Answers>().is_err());
+        assert!("[day01]\npart3 = \"1\"".parse::<Answers>().is_err());
+        assert!("[day01]\npart1 = 1.5".parse::<Answers>().is_err());
+    }
+
+    #[test]
+    fn test_verdict() {
+        let (one, two) = (Answer::Integer(1), Answer::Integer(2));
+        assert_eq!(Verdict::new(Some(&one), &one), Verdict::Pass);
+        assert_eq!(
+            Verdict::new(Some(&one), &two),
+            Verdict::Fail {
+                expected: one.clone()
+            }
+        );
+        assert_eq!(Verdict::new(None, &two), Verdict::Missing);
+        assert_eq!(
+            Verdict::new(Some(&Answer::from("1,2")), &Answer::from("1,2")),
+            Verdict::Pass
+        );
+    }
+}
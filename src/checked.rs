@@ -0,0 +1,125 @@
+//! Arithmetic on answers that can overflow, checked with the `checked` feature
+//!
+//! Without the feature these are the plain operators, which panic in debug builds
+//! and wrap in release builds, so the hot loops don't pay for the checks. With
+//! it, overflows and narrowing conversions are reported as a [`SolveError`].
+use std::fmt::Display;
+
+use crate::error::SolveError;
+
+/// Integer types the solutions compute their answers in
+pub trait Number: Copy + Display {
+    const NAME: &'static str;
+    const ZERO: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn plain_add(self, rhs: Self) -> Self;
+    fn plain_mul(self, rhs: Self) -> Self;
+    fn checked_from_usize(value: usize) -> Option<Self>;
+    fn plain_from_usize(value: usize) -> Self;
+}
+
+macro_rules! impl_number {
+    ($($number:ty),*) => {
+        $(
+            impl Number for $number {
+                const NAME: &'static str = stringify!($number);
+                const ZERO: Self = 0;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$number>::checked_add(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$number>::checked_mul(self, rhs)
+                }
+
+                fn plain_add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+
+                fn plain_mul(self, rhs: Self) -> Self {
+                    self * rhs
+                }
+
+                fn checked_from_usize(value: usize) -> Option<Self> {
+                    value.try_into().ok()
+                }
+
+                fn plain_from_usize(value: usize) -> Self {
+                    value as $number
+                }
+            }
+        )*
+    };
+}
+
+impl_number!(u16, u32, u64, usize);
+
+fn overflow<T: Number>(operation: impl Display) -> SolveError {
+    SolveError::new(format!("{operation} overflows {}", T::NAME))
+}
+
+pub fn add<T: Number>(a: T, b: T) -> Result<T, SolveError> {
+    if cfg!(feature = "checked") {
+        a.checked_add(b)
+            .ok_or_else(|| overflow::<T>(format_args!("{a} + {b}")))
+    } else {
+        Ok(a.plain_add(b))
+    }
+}
+
+pub fn mul<T: Number>(a: T, b: T) -> Result<T, SolveError> {
+    if cfg!(feature = "checked") {
+        a.checked_mul(b)
+            .ok_or_else(|| overflow::<T>(format_args!("{a} * {b}")))
+    } else {
+        Ok(a.plain_mul(b))
+    }
+}
+
+pub fn sum<T: Number>(numbers: impl IntoIterator<Item = T>) -> Result<T, SolveError> {
+    numbers.into_iter().try_fold(T::ZERO, add)
+}
+
+/// Convert a count or an index to the type of the answer
+pub fn narrow<T: Number>(value: usize) -> Result<T, SolveError> {
+    if cfg!(feature = "checked") {
+        T::checked_from_usize(value)
+            .ok_or_else(|| SolveError::new(format!("{value} doesn't fit in {}", T::NAME)))
+    } else {
+        Ok(T::plain_from_usize(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_range() {
+        assert_eq!(add(1_u16, 2), Ok(3));
+        assert_eq!(mul(3_u64, 4), Ok(12));
+        assert_eq!(sum([1_u32, 2, 3]), Ok(6));
+        assert_eq!(narrow::<u16>(7), Ok(7));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        assert_eq!(
+            add(u16::MAX, 1).unwrap_err().reason,
+            "65535 + 1 overflows u16"
+        );
+        assert_eq!(
+            mul(u64::MAX, 2).unwrap_err().reason,
+            "18446744073709551615 * 2 overflows u64"
+        );
+        assert!(sum([u32::MAX, 1]).is_err());
+        assert_eq!(
+            narrow::<u16>(70000).unwrap_err().reason,
+            "70000 doesn't fit in u16"
+        );
+    }
+}
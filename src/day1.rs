@@ -1,75 +1,528 @@
-fn part1(input: &str) -> u32 {
-    let mut first = Vec::new();
-    let mut second = Vec::new();
-    for line in input.lines() {
-        let mut parts = line.split_whitespace().fuse();
-        let num1: u32 = parts.next().unwrap().parse().unwrap();
-        let num2: u32 = parts.next().unwrap().parse().unwrap();
-        first.push(num1);
-        second.push(num2);
+use std::{
+    cmp::Reverse,
+    io::{self, Read},
+};
+
+use crate::{
+    checked,
+    error::{ParseError, SolveError},
+    solution::Solution,
+};
+
+/// Both columns of location IDs, each sorted in ascending order
+#[derive(Debug, Clone)]
+pub struct LocationLists {
+    first: Vec<u32>,
+    second: Vec<u32>,
+}
+
+impl LocationLists {
+    pub fn first(&self) -> &[u32] {
+        &self.first
     }
 
-    first.sort();
-    second.sort();
+    pub fn second(&self) -> &[u32] {
+        &self.second
+    }
+}
 
-    first
-        .iter()
-        .zip(second.iter())
-        .map(|(first, second)| {
-            if first > second {
-                first - second
-            } else {
-                second - first
+/// Parser of the location lists that is fed the bytes of the input as they come in
+///
+/// Numbers are parsed straight from the bytes, without splitting the input into
+/// lines first, so the lists are the only thing that grows with the input.
+struct ListsParser {
+    first: Vec<u32>,
+    second: Vec<u32>,
+    line: usize,
+    /// 1-based column of the next character
+    column: usize,
+    /// Column right after the last character of the line that isn't whitespace
+    end_column: usize,
+    /// Bytes of the number being read and the column it starts at
+    token: Vec<u8>,
+    token_column: usize,
+    numbers: [u32; 2],
+    num_numbers: usize,
+}
+
+impl ListsParser {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            first: Vec::with_capacity(capacity),
+            second: Vec::with_capacity(capacity),
+            line: 1,
+            column: 1,
+            end_column: 1,
+            token: Vec::new(),
+            token_column: 1,
+            numbers: [0; 2],
+            num_numbers: 0,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) -> Result<(), ParseError> {
+        for byte in bytes.iter().copied() {
+            match byte {
+                b'\n' => {
+                    self.end_number()?;
+                    self.end_line()?;
+                }
+                byte if byte.is_ascii_whitespace() => {
+                    self.end_number()?;
+                    self.column += 1;
+                }
+                byte => {
+                    if self.token.is_empty() {
+                        self.token_column = self.column;
+                    }
+                    self.token.push(byte);
+                    // columns count characters, not the continuation bytes of utf-8
+                    if byte & 0b1100_0000 != 0b1000_0000 {
+                        self.column += 1;
+                        self.end_column = self.column;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn end_number(&mut self) -> Result<(), ParseError> {
+        if self.token.is_empty() {
+            return Ok(());
+        }
+        if self.num_numbers == 2 {
+            return Err(ParseError::new(
+                self.line,
+                self.token_column,
+                "expected only 2 numbers",
+            ));
+        }
+
+        let token = String::from_utf8_lossy(&self.token);
+        self.numbers[self.num_numbers] = token.parse().map_err(|e| {
+            ParseError::new(
+                self.line,
+                self.token_column,
+                format!("invalid number `{token}`: {e}"),
+            )
+        })?;
+        self.num_numbers += 1;
+        self.token.clear();
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> Result<(), ParseError> {
+        match self.num_numbers {
+            // empty lines are skipped
+            0 => (),
+            1 => {
+                return Err(ParseError::new(
+                    self.line,
+                    self.end_column,
+                    "expected 2 numbers",
+                ))
+            }
+            _ => {
+                self.first.push(self.numbers[0]);
+                self.second.push(self.numbers[1]);
+            }
+        }
+
+        self.line += 1;
+        self.column = 1;
+        self.end_column = 1;
+        self.num_numbers = 0;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<LocationLists, ParseError> {
+        // the last line doesn't need to end with a newline
+        self.end_number()?;
+        self.end_line()?;
+
+        // part1 needs the lists sorted and part2 doesn't care, so just do it once here
+        self.first.sort_unstable();
+        self.second.sort_unstable();
+
+        Ok(LocationLists {
+            first: self.first,
+            second: self.second,
+        })
+    }
+}
+
+pub fn parse(input: &str) -> Result<LocationLists, ParseError> {
+    // lines of the real input look like `12345   67890`
+    let mut parser = ListsParser::with_capacity(input.len() / 14);
+    parser.push(input.as_bytes())?;
+    parser.finish()
+}
+
+/// Parse the lists while reading them, without holding the whole input in memory
+pub fn parse_reader(mut reader: impl Read) -> Result<LocationLists, ParseError> {
+    let mut parser = ListsParser::with_capacity(0);
+    let mut buffer = [0; 8192];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                return Err(ParseError::new(
+                    parser.line,
+                    parser.column,
+                    format!("can't read the input: {e}"),
+                ))
             }
+        };
+        parser.push(&buffer[..read])?;
+    }
+    parser.finish()
+}
+
+/// Blame the pair of the sorted lists that overflowed, sorting loses the lines of the input
+fn on_pair(error: SolveError, index: usize) -> SolveError {
+    SolveError::new(format!(
+        "pair {} of the sorted lists: {}",
+        index + 1,
+        error.reason
+    ))
+}
+
+/// Blame the location id of the first list whose similarity overflowed
+fn on_location(error: SolveError, id: u32) -> SolveError {
+    SolveError::new(format!("location id {id}: {}", error.reason))
+}
+
+pub fn part1(lists: &LocationLists) -> Result<u32, SolveError> {
+    lists
+        .first
+        .iter()
+        .zip(lists.second.iter())
+        .enumerate()
+        .try_fold(0, |total, (index, (first, second))| {
+            checked::add(total, first.abs_diff(*second)).map_err(|e| on_pair(e, index))
         })
-        .sum()
 }
 
-fn part2(input: &str) -> u32 {
-    let mut first = Vec::new();
-    let mut second = Vec::new();
-    for line in input.lines() {
-        let mut parts = line.split_whitespace().fuse();
-        let num1: u32 = parts.next().unwrap().parse().unwrap();
-        let num2: u32 = parts.next().unwrap().parse().unwrap();
-        first.push(num1);
-        second.push(num2);
+/// How the occurrences of the numbers of the second list are counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counting {
+    /// A table with a count for every value between the smallest and the largest
+    Dense,
+    /// Walk both sorted lists side by side, memory doesn't depend on the values
+    Sparse,
+}
+
+impl Counting {
+    /// Values that are close together get a table, it's faster than walking the lists
+    pub fn for_lists(lists: &LocationLists) -> Self {
+        let range = match (lists.second.first(), lists.second.last()) {
+            (Some(min), Some(max)) => (max - min) as usize + 1,
+            _ => 0,
+        };
+        if range <= (lists.second.len() * 4).max(1 << 16) {
+            Self::Dense
+        } else {
+            Self::Sparse
+        }
     }
+}
+
+/// Sum of the numbers of the first list times how often they show up in the second one
+pub fn similarity_score(lists: &LocationLists, counting: Counting) -> Result<u64, SolveError> {
+    let similarity = |total, (first, occurrences): (u32, u64)| {
+        checked::mul(first as u64, occurrences)
+            .and_then(|similarity| checked::add(total, similarity))
+            .map_err(|e| on_location(e, first))
+    };
 
-    let max_second = *second.iter().max().unwrap() as usize;
-    let mut occurences = vec![0; max_second + 1];
-    for s in &second {
-        occurences[*s as usize] += 1;
+    match counting {
+        Counting::Dense => {
+            let Some((&min, &max)) = lists.second.first().zip(lists.second.last()) else {
+                return Ok(0);
+            };
+            let mut occurrences = vec![0; (max - min) as usize + 1];
+            for second in &lists.second {
+                occurrences[(second - min) as usize] += 1;
+            }
+            lists
+                .first
+                .iter()
+                .map(|first| {
+                    let count = first
+                        .checked_sub(min)
+                        .and_then(|index| occurrences.get(index as usize));
+                    (*first, count.copied().unwrap_or(0))
+                })
+                .try_fold(0, similarity)
+        }
+        Counting::Sparse => occurrences(lists).try_fold(0, |total, (first, count, occurrences)| {
+            let occurrences =
+                checked::mul(count, occurrences).map_err(|e| on_location(e, first))?;
+            similarity(total, (first, occurrences))
+        }),
     }
-    first
+}
+
+/// Every distinct number of the first list, how often it's in the first list and
+/// how often it's in the second one
+fn occurrences(lists: &LocationLists) -> impl Iterator<Item = (u32, u64, u64)> + '_ {
+    // both lists are sorted, so the occurrences of a number are right after the
+    // occurrences of the smaller ones
+    let mut second = lists.second.iter().peekable();
+    lists.first.chunk_by(|a, b| a == b).map(move |firsts| {
+        let first = firsts[0];
+        while second.next_if(|second| **second < first).is_some() {}
+        let mut occurrences = 0;
+        while second.next_if(|second| **second == first).is_some() {
+            occurrences += 1;
+        }
+        (first, firsts.len() as u64, occurrences)
+    })
+}
+
+pub fn part2(lists: &LocationLists) -> Result<u64, SolveError> {
+    similarity_score(lists, Counting::for_lists(lists))
+}
+
+/// Order of the rows of a breakdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakdownOrder {
+    /// The order of the sorted lists
+    Lists,
+    /// The rows that add the most to the answer first, ties stay in the order of the lists
+    Largest,
+}
+
+/// Numbers at the same position of both sorted lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PairDistance {
+    pub first: u32,
+    pub second: u32,
+    pub distance: u32,
+}
+
+/// What a distinct number of the first list adds to the similarity score
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Similarity {
+    pub value: u32,
+    /// How often the number is in the first list
+    pub count: u64,
+    /// How often the number is in the second list
+    pub occurrences: u64,
+    pub contribution: u64,
+}
+
+fn arrange<T>(rows: &mut Vec<T>, order: BreakdownOrder, top: Option<usize>, key: fn(&T) -> u64) {
+    if order == BreakdownOrder::Largest {
+        rows.sort_by_key(|row| Reverse(key(row)));
+    }
+    if let Some(top) = top {
+        rows.truncate(top);
+    }
+}
+
+/// The distances that [`part1`] adds up, to see which pairs dominate the total
+pub fn pair_distances(
+    lists: &LocationLists,
+    order: BreakdownOrder,
+    top: Option<usize>,
+) -> Vec<PairDistance> {
+    let mut pairs: Vec<_> = lists
+        .first
         .iter()
-        .map(|f| *f * occurences.get(*f as usize).unwrap_or(&0))
-        .sum()
+        .zip(lists.second.iter())
+        .map(|(first, second)| PairDistance {
+            first: *first,
+            second: *second,
+            distance: first.abs_diff(*second),
+        })
+        .collect();
+    arrange(&mut pairs, order, top, |pair| pair.distance as u64);
+    pairs
+}
+
+/// What every number of the first list adds to the answer of [`part2`]
+pub fn similarity_breakdown(
+    lists: &LocationLists,
+    order: BreakdownOrder,
+    top: Option<usize>,
+) -> Result<Vec<Similarity>, SolveError> {
+    let mut similarities = occurrences(lists)
+        .map(|(value, count, occurrences)| {
+            Ok(Similarity {
+                value,
+                count,
+                occurrences,
+                contribution: checked::mul(value as u64, count)
+                    .and_then(|similarity| checked::mul(similarity, occurrences))
+                    .map_err(|e| on_location(e, value))?,
+            })
+        })
+        .collect::<Result<Vec<_>, SolveError>>()?;
+    arrange(&mut similarities, order, top, |similarity| {
+        similarity.contribution
+    });
+    Ok(similarities)
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = LocationLists;
+    type Answer1 = u32;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const INPUT: &str = include_str!("../inputs/day01.example.txt");
+
     #[test]
     fn test_part1() {
-        let input = r#"3   4
-        4   3
-        2   5
-        1   3
-        3   9
-        3   3"#;
-        assert_eq!(part1(input), 11);
+        assert_eq!(part1(&parse(INPUT).unwrap()), Ok(11));
     }
 
     #[test]
     fn test_part2() {
-        let input = r#"3   4
-        4   3
-        2   5
-        1   3
-        3   9
-        3   3"#;
-        assert_eq!(part2(input), 31);
+        assert_eq!(part2(&parse(INPUT).unwrap()), Ok(31));
+    }
+
+    #[test]
+    fn test_counting() {
+        let lists = parse(INPUT).unwrap();
+        assert_eq!(Counting::for_lists(&lists), Counting::Dense);
+        assert_eq!(similarity_score(&lists, Counting::Sparse), Ok(31));
+
+        // a dense table would need 4 billion entries
+        let lists = parse("4000000000   4000000000\n1   4000000000\n3   1").unwrap();
+        assert_eq!(Counting::for_lists(&lists), Counting::Sparse);
+        assert_eq!(part2(&lists), Ok(8_000_000_001));
+    }
+
+    #[test]
+    fn test_pair_distances() {
+        let lists = parse(INPUT).unwrap();
+        let pairs = pair_distances(&lists, BreakdownOrder::Lists, None);
+        let distances: Vec<_> = pairs.iter().map(|pair| pair.distance).collect();
+        assert_eq!(distances, vec![2, 1, 0, 1, 2, 5]);
+        assert_eq!(distances.iter().sum::<u32>(), part1(&lists).unwrap());
+
+        let largest = pair_distances(&lists, BreakdownOrder::Largest, Some(2));
+        assert_eq!(
+            largest,
+            vec![
+                PairDistance {
+                    first: 4,
+                    second: 9,
+                    distance: 5
+                },
+                PairDistance {
+                    first: 1,
+                    second: 3,
+                    distance: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_similarity_breakdown() {
+        let lists = parse(INPUT).unwrap();
+        let similarities = similarity_breakdown(&lists, BreakdownOrder::Lists, None).unwrap();
+        let rows: Vec<_> = similarities
+            .iter()
+            .map(|s| (s.value, s.count, s.occurrences, s.contribution))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![(1, 1, 0, 0), (2, 1, 0, 0), (3, 3, 3, 27), (4, 1, 1, 4)]
+        );
+
+        let largest = similarity_breakdown(&lists, BreakdownOrder::Largest, Some(1)).unwrap();
+        assert_eq!(largest.len(), 1);
+        assert_eq!(largest[0].value, 3);
+        assert!(
+            similarity_breakdown(&lists, BreakdownOrder::Largest, Some(0))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        let lists = parse("0   4000000000\n1   1\n0   4000000000\n").unwrap();
+        assert_eq!(
+            part1(&lists).unwrap_err().to_string(),
+            "pair 3 of the sorted lists: 4000000001 + 3999999999 overflows u32"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("3   4\n4 x\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let error = parse("3   4\n4\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+
+        let error = parse("3 4 5").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+
+        let error = parse("3   4\n\u{e9}4   4\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+
+        let error = parse("3   4\n4   99999999999").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+    }
+
+    #[test]
+    fn test_parse_crlf_and_trailing_whitespace() {
+        let lists = parse("3   4  \r\n4\t3\r\n\r\n").unwrap();
+        assert_eq!(lists.first, vec![3, 4]);
+        assert_eq!(lists.second, vec![3, 4]);
+    }
+
+    /// Hands out the input a few bytes at a time, splitting numbers and characters
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            let read = self.0.len().min(buffer.len()).min(3);
+            buffer[..read].copy_from_slice(&self.0[..read]);
+            self.0 = &self.0[read..];
+            Ok(read)
+        }
+    }
+
+    #[test]
+    fn test_parse_reader() {
+        let lists = parse_reader(Trickle(INPUT.as_bytes())).unwrap();
+        assert_eq!(part1(&lists), Ok(11));
+        assert_eq!(part2(&lists), Ok(31));
+
+        let error = parse_reader(Trickle("3   4\n1 \u{e9}\u{e9}".as_bytes())).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert!(error.reason.starts_with("invalid number `\u{e9}\u{e9}`"));
     }
 }
@@ -1,79 +1,62 @@
-use std::collections::HashSet;
-
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-struct Position {
-    x: usize,
-    y: usize,
+use std::{collections::HashSet, str::FromStr};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    checked,
+    error::{ParseError, SolveError},
+    grid::{Grid, Point},
+    solution::Solution,
+};
+
+#[derive(Debug)]
+pub struct TrailMap {
+    grid: Grid<u8>,
+    start_positions: Vec<Point>,
 }
 
-impl Position {
-    fn neighbors(&self, num_rows: usize, num_columns: usize) -> Vec<Position> {
-        let mut neighbors = Vec::new();
-        if self.x + 1 < num_rows {
-            neighbors.push(Position {
-                x: self.x + 1,
-                y: self.y,
-            })
-        }
-
-        if self.y + 1 < num_columns {
-            neighbors.push(Position {
-                x: self.x,
-                y: self.y + 1,
-            })
-        }
-
-        if self.x > 0 {
-            neighbors.push(Position {
-                x: self.x - 1,
-                y: self.y,
-            })
-        }
-
-        if self.y > 0 {
-            neighbors.push(Position {
-                x: self.x,
-                y: self.y - 1,
-            })
-        }
+impl TrailMap {
+    /// Positions with a height of 0, where trails start
+    pub fn start_positions(&self) -> &[Point] {
+        &self.start_positions
+    }
 
-        neighbors
+    /// Number of 9 height positions reachable from `start`
+    pub fn score(&self, start: Point) -> u32 {
+        find_num_trails(&self.grid, start)
     }
-}
 
-struct TrailMap {
-    grid: Vec<Vec<u8>>,
-    start_positions: Vec<Position>,
+    /// Number of distinct trails from `start` to any 9 height position
+    pub fn rating(&self, start: Point) -> u32 {
+        get_trail_ratings(&self.grid, start)
+    }
 }
 
-impl TrailMap {
-    fn from_str(input: &str) -> Self {
-        let mut grid = Vec::new();
-        let mut start_positions = Vec::new();
-        for (y, line) in input.lines().enumerate() {
-            let mut row = Vec::with_capacity(line.len());
-            for (x, c) in line.char_indices() {
-                let digit = c.to_digit(10).unwrap() as u8;
-                row.push(digit);
-
-                if digit == 0 {
-                    start_positions.push(Position { x, y });
-                }
-            }
-            grid.push(row);
-        }
-        Self {
+impl FromStr for TrailMap {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let grid = Grid::parse(input, |c| match c.to_digit(10) {
+            Some(digit) => Ok(digit as u8),
+            None => Err(format!("expected a height digit, found `{c}`")),
+        })?;
+        let start_positions = grid
+            .iter()
+            .filter(|(_, height)| **height == 0)
+            .map(|(point, _)| point)
+            .collect();
+        Ok(Self {
             grid,
             start_positions,
-        }
+        })
     }
 }
 
-fn insert_possible_neighbors(stack: &mut Vec<Position>, current: Position, grid: &[Vec<u8>]) {
-    let current_value = grid[current.y][current.x];
-    for neighbor in current.neighbors(grid.len(), grid[0].len()) {
-        let neighbor_value = grid[neighbor.y][neighbor.x];
-        if current_value + 1 == neighbor_value {
+fn insert_possible_neighbors(stack: &mut Vec<Point>, current: Point, grid: &Grid<u8>) {
+    let current_value = grid[current];
+    for neighbor in grid.neighbors4(current) {
+        if current_value + 1 == grid[neighbor] {
             stack.push(neighbor);
         }
     }
@@ -85,7 +68,7 @@ fn insert_possible_neighbors(stack: &mut Vec<Position>, current: Position, grid:
 /// increasing by exactly 1 each time and ends in a 9
 /// Important to note the grid can be represented as a directed graph, as edges are only
 /// from a node to a node with a value that is exactly 1 higher
-fn find_num_trails(grid: &[Vec<u8>], position: Position) -> u32 {
+fn find_num_trails(grid: &Grid<u8>, position: Point) -> u32 {
     let mut stack = vec![position];
     let mut visited = HashSet::new();
     let mut num_trails = 0;
@@ -93,7 +76,7 @@ fn find_num_trails(grid: &[Vec<u8>], position: Position) -> u32 {
         if visited.contains(&current) {
             continue;
         }
-        let current_value = grid[current.y][current.x];
+        let current_value = grid[current];
         visited.insert(current);
         if current_value == 9 {
             num_trails += 1;
@@ -107,11 +90,11 @@ fn find_num_trails(grid: &[Vec<u8>], position: Position) -> u32 {
 }
 
 /// This feels very not optimized...
-fn get_trail_ratings(grid: &[Vec<u8>], position: Position) -> u32 {
+fn get_trail_ratings(grid: &Grid<u8>, position: Point) -> u32 {
     let mut stack = vec![position];
     let mut rating = 0;
     while let Some(current) = stack.pop() {
-        let current_value = grid[current.y][current.x];
+        let current_value = grid[current];
         if current_value == 9 {
             rating += 1;
             continue;
@@ -122,42 +105,79 @@ fn get_trail_ratings(grid: &[Vec<u8>], position: Position) -> u32 {
     rating
 }
 
-pub fn part1(input: &str) -> u32 {
-    let trail_map = TrailMap::from_str(input);
-    trail_map
-        .start_positions
-        .iter()
-        .map(|start| find_num_trails(&trail_map.grid, *start))
-        .sum()
+pub fn parse(input: &str) -> Result<TrailMap, ParseError> {
+    input.parse()
+}
+
+pub fn part1(trail_map: &TrailMap) -> Result<u32, SolveError> {
+    #[cfg(feature = "parallel")]
+    let starts = trail_map.start_positions.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let starts = trail_map.start_positions.iter();
+
+    // a single start has at most 4 * 3^8 trails, so only the total can overflow
+    checked::narrow(starts.map(|start| trail_map.score(*start) as usize).sum())
+}
+
+pub fn part2(trail_map: &TrailMap) -> Result<u32, SolveError> {
+    #[cfg(feature = "parallel")]
+    let starts = trail_map.start_positions.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let starts = trail_map.start_positions.iter();
+
+    checked::narrow(starts.map(|start| trail_map.rating(*start) as usize).sum())
 }
 
-pub fn part2(input: &str) -> u32 {
-    let trail_map = TrailMap::from_str(input);
-    trail_map
-        .start_positions
-        .iter()
-        .map(|start| get_trail_ratings(&trail_map.grid, *start))
-        .sum()
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = TrailMap;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError> {
+        part2(input)
+    }
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = r#"89010123
-78121874
-87430965
-96549874
-45678903
-32019012
-01329801
-10456732"#;
+    const INPUT: &str = include_str!("../inputs/day10.example.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 36);
+        assert_eq!(part1(&parse(INPUT).unwrap()), Ok(36));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 81);
+        assert_eq!(part2(&parse(INPUT).unwrap()), Ok(81));
+    }
+
+    #[test]
+    fn test_non_square_map() {
+        let map = parse("0123456789\n9876543210").unwrap();
+        assert_eq!(part1(&map), Ok(4));
+        assert_eq!(part2(&map), Ok(4));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("8901\n78.1").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let error = parse("8901\n781\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
     }
 }
@@ -1,7 +1,13 @@
 use std::collections::HashMap;
 
-#[derive(Debug)]
-struct Stones {
+use crate::{
+    checked,
+    error::{self, ParseError, SolveError},
+    solution::Solution,
+};
+
+#[derive(Debug, Clone)]
+pub struct Stones {
     stones: Vec<u64>,
 }
 
@@ -15,20 +21,25 @@ fn get_num_digits(num: u64) -> u32 {
 }
 
 impl Stones {
-    fn from_line(line: &str) -> Stones {
+    pub fn stones(&self) -> &[u64] {
+        &self.stones
+    }
+
+    pub fn from_line(line: &str) -> Result<Stones, ParseError> {
         let stones = line
             .split_whitespace()
-            .map(|x| x.parse().unwrap())
-            .collect();
-        Stones { stones }
+            .map(|stone| error::parse_number(1, line, stone))
+            .collect::<Result<_, _>>()?;
+        Ok(Stones { stones })
     }
 
-    fn blink(&mut self) {
+    /// Blink once, all the stones change at the same time
+    pub fn blink(&mut self) -> Result<(), SolveError> {
         let mut to_add = Vec::new();
         for stone in self.stones.iter_mut() {
             if stone == &0 {
                 *stone = 1
-            } else if get_num_digits(*stone) % 2 == 0 {
+            } else if get_num_digits(*stone).is_multiple_of(2) {
                 // split into 2 stones
                 let num_digits = get_num_digits(*stone);
                 let left_digits = *stone / (10_u64.pow(num_digits / 2));
@@ -36,7 +47,8 @@ impl Stones {
                 *stone = left_digits;
                 to_add.push(right_digits);
             } else {
-                *stone *= 2024;
+                // all the stones are on the first line
+                *stone = checked::mul(*stone, 2024).map_err(|e| e.on_line(1))?;
             }
         }
 
@@ -44,74 +56,136 @@ impl Stones {
         for stone in to_add {
             self.stones.push(stone);
         }
+
+        Ok(())
+    }
+}
+
+pub fn parse(input: &str) -> Result<Stones, ParseError> {
+    let mut lines = error::lines(input).filter(|(_, line)| !line.is_empty());
+    let stones = match lines.next() {
+        Some((line_number, line)) => Stones::from_line(line).map_err(|e| e.on_line(line_number))?,
+        None => Stones { stones: Vec::new() },
+    };
+    if let Some((line_number, _)) = lines.next() {
+        return Err(ParseError::new(
+            line_number,
+            1,
+            "expected all stones on a single line",
+        ));
     }
+
+    Ok(stones)
 }
 
-pub fn part1(input: &str) -> u64 {
-    let mut stones = Stones::from_line(input);
+pub fn part1(stones: &Stones) -> Result<u64, SolveError> {
+    let mut stones = stones.clone();
     for _ in 0..25 {
-        stones.blink();
-        eprintln!("{:?}", stones.stones);
+        stones.blink()?;
+        log::trace!("{:?}", stones.stones);
     }
-    stones.stones.len() as u64
+    checked::narrow(stones.stones.len())
 }
 
 /// Instead of simulating each stone individually, keep a frequency hashmap for each stone
 /// and its number of occurrences. This way instead of simulating each stone, we can
 /// simulate the stone once and update the result for all stones.
 /// Thank you gpt for the hints on this one :)
-fn blink_with_frequencies(mut frequencies: HashMap<u64, u64>, iterations: u8) -> u64 {
+pub fn blink_with_frequencies(
+    mut frequencies: HashMap<u64, u64>,
+    iterations: u8,
+) -> Result<u64, SolveError> {
+    // all the stones are on the first line
+    fn add_stones(
+        frequencies: &mut HashMap<u64, u64>,
+        stone: u64,
+        count: u64,
+    ) -> Result<(), SolveError> {
+        let total = frequencies.entry(stone).or_insert(0);
+        *total = checked::add(*total, count).map_err(|e| e.on_line(1))?;
+        Ok(())
+    }
+
     for i in 0..iterations {
-        eprintln!("{:?}", i);
+        log::debug!("blink {i}: {} different stones", frequencies.len());
         let mut new_frequencies = HashMap::new();
         for (num, count) in frequencies.iter() {
             if num == &0 {
-                new_frequencies
-                    .entry(1)
-                    .and_modify(|x| *x += *count)
-                    .or_insert(*count);
-            } else if get_num_digits(*num) % 2 == 0 {
+                add_stones(&mut new_frequencies, 1, *count)?;
+            } else if get_num_digits(*num).is_multiple_of(2) {
                 let num_digits = get_num_digits(*num);
                 let left_digits = *num / (10_u64.pow(num_digits / 2));
                 let right_digits = *num % (10_u64.pow(num_digits / 2));
 
-                new_frequencies
-                    .entry(left_digits)
-                    .and_modify(|x| *x += *count)
-                    .or_insert(*count);
-
-                new_frequencies
-                    .entry(right_digits)
-                    .and_modify(|x| *x += *count)
-                    .or_insert(*count);
+                add_stones(&mut new_frequencies, left_digits, *count)?;
+                add_stones(&mut new_frequencies, right_digits, *count)?;
             } else {
-                new_frequencies
-                    .entry(*num * 2024)
-                    .and_modify(|x| *x += *count)
-                    .or_insert(*count);
+                let stone = checked::mul(*num, 2024).map_err(|e| e.on_line(1))?;
+                add_stones(&mut new_frequencies, stone, *count)?;
             }
         }
         frequencies = new_frequencies;
     }
-    frequencies.values().sum()
+    checked::sum(frequencies.values().copied()).map_err(|e| e.on_line(1))
 }
 
-pub fn part2(input: &str) -> u64 {
-    let mut stones = Stones::from_line(input);
+pub fn part2(stones: &Stones) -> Result<u64, SolveError> {
     let mut frequencies = HashMap::new();
-    for stone in stones.stones {
+    for stone in stones.stones.iter().copied() {
         *frequencies.entry(stone).or_insert(0) += 1;
     }
 
     blink_with_frequencies(frequencies, 75)
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Stones;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError> {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
-    const INPUT: &str = r#"125 17"#;
+    const INPUT: &str = include_str!("../inputs/day11.example.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 55312);
+        assert_eq!(part1(&parse(INPUT).unwrap()), Ok(55312));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("125 1x7").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+
+        let error = parse("125\n17").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        let stones = parse("9999999999999999999").unwrap();
+        let error = part1(&stones).unwrap_err();
+        assert_eq!(error.line, Some(1));
+        assert_eq!(error.reason, "9999999999999999999 * 2024 overflows u64");
+        assert!(part2(&stones).is_err());
     }
 }
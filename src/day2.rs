@@ -1,178 +1,375 @@
-use std::{collections::HashMap, str::FromStr};
-
-fn maybe_skip_nth<T>(it: impl Iterator<Item = T>, skip: Option<usize>) -> impl Iterator<Item = T> {
-    it.into_iter()
-        .enumerate()
-        .filter_map(move |(i, item)| match &skip {
-            Some(skip) => {
-                if i == *skip {
-                    None
-                } else {
-                    Some(item)
-                }
-            }
-            None => Some(item),
-        })
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    checked,
+    error::{self, ParseError, SolveError},
+    solution::Solution,
+};
+
+/// Direction the levels of a safe report have to go in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Monotonicity {
+    /// All levels go up, or all levels go down
+    Either,
+    Increasing,
+    Decreasing,
+    /// Every step can go up or down, only its size matters
+    Any,
+}
+
+/// What makes a report safe
+///
+/// The default is the policy of part 1: steps of 1 to 3 levels, all in the same
+/// direction, and no level removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SafetyPolicy {
+    /// Smallest difference between adjacent levels, 0 allows equal levels
+    pub min_step: u8,
+    /// Largest difference between adjacent levels
+    pub max_step: u8,
+    pub monotonicity: Monotonicity,
+    /// How many levels can be removed to make the report safe
+    pub tolerance: usize,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self::new(1, 3, Monotonicity::Either, 0)
+    }
+}
+
+impl SafetyPolicy {
+    pub const fn new(
+        min_step: u8,
+        max_step: u8,
+        monotonicity: Monotonicity,
+        tolerance: usize,
+    ) -> Self {
+        Self {
+            min_step,
+            max_step,
+            monotonicity,
+            tolerance,
+        }
+    }
+
+    /// The same policy with another number of removable levels
+    pub const fn with_tolerance(self, tolerance: usize) -> Self {
+        Self { tolerance, ..self }
+    }
+
+    /// Directions to check a report in, a report is safe if it's safe in one of them
+    fn directions(&self) -> &'static [Monotonicity] {
+        match self.monotonicity {
+            Monotonicity::Either => &[Monotonicity::Increasing, Monotonicity::Decreasing],
+            Monotonicity::Increasing => &[Monotonicity::Increasing],
+            Monotonicity::Decreasing => &[Monotonicity::Decreasing],
+            Monotonicity::Any => &[Monotonicity::Any],
+        }
+    }
+
+    /// Whether going from level `a` to level `b` is a safe step in the direction
+    fn is_safe_step(&self, a: u8, b: u8, direction: Monotonicity) -> bool {
+        let right_direction = match direction {
+            Monotonicity::Increasing => a <= b,
+            Monotonicity::Decreasing => a >= b,
+            Monotonicity::Either | Monotonicity::Any => true,
+        };
+        right_direction && (self.min_step..=self.max_step).contains(&a.abs_diff(b))
+    }
 }
 
+#[derive(Debug)]
 pub struct Report {
     levels: Vec<u8>,
 }
 
 impl Report {
-    fn from_line(line: &str) -> Self {
+    pub fn levels(&self) -> &[u8] {
+        &self.levels
+    }
+
+    pub fn from_line(line: &str) -> Result<Self, ParseError> {
         let levels = line
             .split_whitespace()
-            .map(|x| x.parse().unwrap())
-            .collect();
-        Self { levels }
-    }
-
-    fn is_safe(&self) -> bool {
-        let mut asc = None;
-        for (a, b) in self.levels.iter().zip(self.levels.iter().skip(1)) {
-            match asc {
-                Some(asc) => {
-                    if asc {
-                        if a < b {
-                            return false;
-                        }
-                    } else {
-                        if a > b {
-                            return false;
-                        }
-                    }
-                }
-                None => {
-                    if a > b {
-                        asc = Some(true);
-                    } else {
-                        asc = Some(false);
-                    }
-                }
-            }
+            .map(|level| error::parse_number(1, line, level))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { levels })
+    }
 
-            let diff = a.abs_diff(*b);
-            if diff < 1 || diff > 3 {
-                return false;
-            }
-        }
+    /// Whether the report is safe without removing any level, like in part 1
+    pub fn is_safe(&self) -> bool {
+        self.is_safe_with(&SafetyPolicy::default())
+    }
 
-        true
-    }
-
-    fn invalid_index(&self, skip: Option<usize>) -> Option<usize> {
-        let mut asc = None;
-        for (i, (a, b)) in maybe_skip_nth(self.levels.iter(), skip)
-            .zip(maybe_skip_nth(
-                self.levels.iter().skip(1),
-                skip.map(|x| if x >= 1 { x - 1 } else { x }),
-            ))
-            .enumerate()
-        {
-            // eprint!("{}, ", a);
-            match asc {
-                Some(asc) => {
-                    if asc {
-                        if a < b {
-                            return Some(i + 1);
-                        }
-                    } else {
-                        if a > b {
-                            return Some(i + 1);
-                        }
-                    }
-                }
-                None => {
-                    if a > b {
-                        asc = Some(true);
-                    } else {
-                        asc = Some(false);
-                    }
-                }
-            }
+    /// Whether the report is safe after removing at most one level, like in part 2
+    pub fn is_safe_with_toleration(&self) -> bool {
+        self.is_safe_with(&SafetyPolicy::default().with_tolerance(1))
+    }
 
-            let diff = a.abs_diff(*b);
-            if diff < 1 || diff > 3 {
-                return Some(i + 1);
-            }
+    /// Whether the report is safe by the rules of the policy
+    pub fn is_safe_with(&self, policy: &SafetyPolicy) -> bool {
+        // a single level left is always safe
+        if self.levels.len() <= policy.tolerance.saturating_add(1) {
+            return true;
         }
 
-        None
+        policy
+            .directions()
+            .iter()
+            .any(|direction| match policy.tolerance {
+                0 => (1..self.levels.len())
+                    .all(|i| policy.is_safe_step(self.levels[i - 1], self.levels[i], *direction)),
+                1 => self.is_safe_without_one(policy, *direction),
+                _ => self.is_safe_without_some(policy, *direction),
+            })
     }
 
-    fn is_safe_with_toleration(&self) -> bool {
-        let mut counter = HashMap::new();
-        for i in &self.levels {
-            counter.entry(*i).and_modify(|x| *x += 1).or_insert(1);
-        }
-        for l in counter.values() {
-            if *l > 2 {
-                return false;
-            }
-        }
-
-        let num_2 = counter.values().filter(|x| (**x == 2)).count();
-        if num_2 > 1 {
-            return false;
-        }
+    /// Whether removing at most one level leaves only safe steps
+    ///
+    /// Without level `k`, the report is safe when the levels before `k` are safe,
+    /// the levels after `k` are safe, and the levels on both sides of `k` make a
+    /// safe step. The longest safe prefix and the longest safe suffix answer the
+    /// first two questions for every `k` at once, so every removal is checked in
+    /// constant time.
+    fn is_safe_without_one(&self, policy: &SafetyPolicy, direction: Monotonicity) -> bool {
+        let levels = &self.levels;
+        let len = levels.len();
+        let is_safe_step =
+            |i: usize, j: usize| policy.is_safe_step(levels[i], levels[j], direction);
 
-        let invalid_index = self.invalid_index(None);
-        let Some(index) = invalid_index else {
+        // levels[..=prefix_end] and levels[suffix_start..] only have safe steps
+        let Some(first_unsafe) = (1..len).find(|i| !is_safe_step(i - 1, *i)) else {
             return true;
         };
+        let prefix_end = first_unsafe - 1;
+        let suffix_start = (1..len)
+            .rev()
+            .find(|i| !is_safe_step(i - 1, *i))
+            .unwrap_or(0);
 
-        if self.invalid_index(Some(index)).is_none() {
-            return true;
-        }
-
-        if self.invalid_index(Some(0)).is_none() {
-            return true;
-        }
+        (0..len).any(|k| {
+            k <= prefix_end + 1
+                && k + 1 >= suffix_start
+                && (k == 0 || k == len - 1 || is_safe_step(k - 1, k + 1))
+        })
+    }
 
-        if index >= 1 {
-            if self.invalid_index(Some(index - 1)).is_none() {
-                eprintln!("index:{} - {:?}", index, self.levels);
-                return true;
+    /// Whether removing at most `policy.tolerance` levels leaves only safe steps
+    ///
+    /// `kept[i][r]` is whether the levels up to `i` can be made safe by removing `r`
+    /// of the levels before `i` and keeping level `i`. The level kept before `i` is
+    /// one of the `r + 1` levels right before it, so this takes O(n·k²) for `k`
+    /// removable levels.
+    fn is_safe_without_some(&self, policy: &SafetyPolicy, direction: Monotonicity) -> bool {
+        let levels = &self.levels;
+        let len = levels.len();
+        let tolerance = policy.tolerance;
+        let mut kept = vec![false; len * (tolerance + 1)];
+        for i in 0..len {
+            for removed in 0..=tolerance {
+                // removing every level before `i` leaves nothing to step from
+                kept[i * (tolerance + 1) + removed] = removed == i
+                    || (0..(removed + 1).min(i)).any(|skipped| {
+                        let previous = i - 1 - skipped;
+                        kept[previous * (tolerance + 1) + removed - skipped]
+                            && policy.is_safe_step(levels[previous], levels[i], direction)
+                    });
             }
         }
 
-        false
+        // the levels after the last kept one are removed too
+        (0..len).any(|i| {
+            (0..=tolerance).any(|removed| {
+                kept[i * (tolerance + 1) + removed] && removed + (len - 1 - i) <= tolerance
+            })
+        })
     }
 }
 
-pub fn part1(inputs: &str) -> u16 {
-    let reports = inputs.lines().map(|line| Report::from_line(line));
-    reports.into_iter().map(|r| r.is_safe() as u16).sum()
+/// Number of reports that are safe with the policy
+pub fn count_safe(reports: &[Report], policy: &SafetyPolicy) -> Result<u16, SolveError> {
+    #[cfg(feature = "parallel")]
+    let reports = reports.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let reports = reports.iter();
+
+    checked::narrow(reports.filter(|r| r.is_safe_with(policy)).count())
+}
+
+pub fn parse(input: &str) -> Result<Vec<Report>, ParseError> {
+    error::lines(input)
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line_number, line)| Report::from_line(line).map_err(|e| e.on_line(line_number)))
+        .collect()
+}
+
+pub fn part1(reports: &[Report]) -> Result<u16, SolveError> {
+    count_safe(reports, &SafetyPolicy::default())
+}
+
+pub fn part2(reports: &[Report]) -> Result<u16, SolveError> {
+    count_safe(reports, &SafetyPolicy::default().with_tolerance(1))
 }
 
-pub fn part2(inputs: &str) -> u16 {
-    let reports = inputs.lines().map(|line| Report::from_line(line));
-    reports
-        .into_iter()
-        .map(|r| r.is_safe_with_toleration() as u16)
-        .sum()
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Report>;
+    type Answer1 = u16;
+    type Answer2 = u16;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUTS: &str = r#"7 6 4 2 1
-1 2 7 8 9
-9 7 6 2 1
-1 3 2 4 5
-8 6 4 4 1
-1 3 6 7 9
-"#;
+    const INPUTS: &str = include_str!("../inputs/day02.example.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUTS), 2);
+        assert_eq!(part1(&parse(INPUTS).unwrap()), Ok(2));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUTS), 4);
+        assert_eq!(part2(&parse(INPUTS).unwrap()), Ok(4));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("7 6 4\n1 2 300\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+
+        let error = parse("7 6 -4").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+    }
+
+    /// Try every way to remove up to `policy.tolerance` levels
+    fn is_safe_brute_force(report: &Report, policy: &SafetyPolicy) -> bool {
+        report.is_safe_with(&policy.with_tolerance(0))
+            || policy.tolerance > 0
+                && (0..report.levels.len()).any(|skip| {
+                    let mut levels = report.levels.clone();
+                    levels.remove(skip);
+                    is_safe_brute_force(
+                        &Report { levels },
+                        &policy.with_tolerance(policy.tolerance - 1),
+                    )
+                })
+    }
+
+    /// Every report of `len` levels between 0 and `max`, in lexicographic order
+    fn all_reports(len: u32, max: u8) -> impl Iterator<Item = Report> {
+        let base = max as usize + 1;
+        (0..base.pow(len)).map(move |mut index| {
+            let levels = (0..len)
+                .map(|_| {
+                    let level = (index % base) as u8;
+                    index /= base;
+                    level
+                })
+                .collect();
+            Report { levels }
+        })
+    }
+
+    #[test]
+    fn test_toleration_of_all_short_reports() {
+        // steps of up to 5 cover safe steps, steps that are too big, and equal levels
+        for len in 0..=7 {
+            for report in all_reports(len, 5) {
+                assert_eq!(
+                    report.is_safe_with_toleration(),
+                    is_safe_brute_force(&report, &SafetyPolicy::default().with_tolerance(1)),
+                    "levels {:?}",
+                    report.levels
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_toleration_of_all_reports_with_big_steps() {
+        for len in 0..=4 {
+            for report in all_reports(len, 12) {
+                assert_eq!(
+                    report.is_safe_with_toleration(),
+                    is_safe_brute_force(&report, &SafetyPolicy::default().with_tolerance(1)),
+                    "levels {:?}",
+                    report.levels
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_toleration_at_the_limits() {
+        let safe = |line| Report::from_line(line).unwrap().is_safe_with_toleration();
+        assert!(safe("255 0 254 251"));
+        assert!(safe("0 255 1 4"));
+        assert!(safe("1 2 3 4 4"));
+        assert!(!safe("1 2 3 3 3"));
+        assert!(!safe("5 1 2 3 0"));
+    }
+
+    #[test]
+    fn test_policies_of_all_short_reports() {
+        let policies = [
+            SafetyPolicy::new(1, 3, Monotonicity::Either, 2),
+            SafetyPolicy::new(1, 3, Monotonicity::Either, 3),
+            SafetyPolicy::new(0, 2, Monotonicity::Increasing, 1),
+            SafetyPolicy::new(2, 4, Monotonicity::Decreasing, 2),
+            SafetyPolicy::new(1, 1, Monotonicity::Any, 0),
+            SafetyPolicy::new(1, 2, Monotonicity::Any, 2),
+        ];
+        for policy in policies {
+            for len in 0..=6 {
+                for report in all_reports(len, 4) {
+                    assert_eq!(
+                        report.is_safe_with(&policy),
+                        is_safe_brute_force(&report, &policy),
+                        "levels {:?} with {policy:?}",
+                        report.levels
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_policy() {
+        let report = Report::from_line("1 3 6 6 10 9 12").unwrap();
+        let policy = SafetyPolicy::new(1, 3, Monotonicity::Increasing, 2);
+        assert!(report.is_safe_with(&policy));
+        assert!(!report.is_safe_with(&policy.with_tolerance(1)));
+        assert!(!report.is_safe_with(&SafetyPolicy {
+            monotonicity: Monotonicity::Decreasing,
+            ..policy
+        }));
+        assert!(report.is_safe_with(&SafetyPolicy::new(0, 4, Monotonicity::Any, 0)));
+        assert!(report.is_safe_with(&SafetyPolicy::new(9, 1, Monotonicity::Any, usize::MAX)));
+        assert_eq!(
+            count_safe(
+                &parse(INPUTS).unwrap(),
+                &SafetyPolicy::default().with_tolerance(6)
+            ),
+            Ok(6)
+        );
     }
 }
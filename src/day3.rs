@@ -1,4 +1,8 @@
-use std::{slice, str::Chars};
+use crate::{
+    checked,
+    error::{ParseError, SolveError},
+    solution::Solution,
+};
 
 pub struct InstructionParser<'a> {
     input: &'a [u8],
@@ -42,8 +46,8 @@ fn parse_1_to_3_digits(input: &[u8], cursor: usize) -> Option<(u16, usize)> {
 }
 
 impl<'a> InstructionParser<'a> {
+    /// Non ascii characters are skipped like any other character that isn't an instruction
     pub fn new(input: &'a str, parse_enabled: bool) -> Self {
-        debug_assert!(input.is_ascii());
         Self {
             input: input.as_bytes(),
             cursor: 0,
@@ -52,14 +56,23 @@ impl<'a> InstructionParser<'a> {
         }
     }
 
+    /// 1-based line of the input the parser is at
+    pub fn line(&self) -> usize {
+        self.input[..self.cursor]
+            .iter()
+            .filter(|c| **c == b'\n')
+            .count()
+            + 1
+    }
+
     fn get_do_instruction(&self) -> Option<bool> {
         let d = self.input.get(self.cursor)?;
         let o = self.input.get(self.cursor + 1)?;
         let first = self.input.get(self.cursor + 2)?;
         let second = self.input.get(self.cursor + 3)?;
         match (d, o, first, second) {
-            (b'd', b'o', b'(', b')') => return Some(true),
-            _ => return Some(false),
+            (b'd', b'o', b'(', b')') => Some(true),
+            _ => Some(false),
         }
     }
 
@@ -72,8 +85,8 @@ impl<'a> InstructionParser<'a> {
         let first = self.input.get(self.cursor + 5)?;
         let second = self.input.get(self.cursor + 6)?;
         match (d, o, n, ti, t, first, second) {
-            (b'd', b'o', b'n', b'\'', b't', b'(', b')') => return Some(true),
-            _ => return Some(false),
+            (b'd', b'o', b'n', b'\'', b't', b'(', b')') => Some(true),
+            _ => Some(false),
         }
     }
 
@@ -93,19 +106,19 @@ impl<'a> InstructionParser<'a> {
         }
 
         // now we expect 1 to 3 digits
-        let (digit1, fast_cursor) = parse_1_to_3_digits(&self.input, fast_cursor + 4)?;
+        let (digit1, fast_cursor) = parse_1_to_3_digits(self.input, fast_cursor + 4)?;
         let seperator = self.input.get(fast_cursor)?;
         if *seperator != b',' {
             return None;
         }
 
-        let (digit2, fast_cursor) = parse_1_to_3_digits(&self.input, fast_cursor + 1)?;
+        let (digit2, fast_cursor) = parse_1_to_3_digits(self.input, fast_cursor + 1)?;
         let final_sep = self.input.get(fast_cursor)?;
         if *final_sep != b')' {
             return None;
         }
 
-        return Some((digit1 as u32 * digit2 as u32, fast_cursor + 1));
+        Some((digit1 as u32 * digit2 as u32, fast_cursor + 1))
     }
 }
 
@@ -117,17 +130,14 @@ impl<'a> Iterator for InstructionParser<'a> {
             let do_inst = self.get_do_instruction();
             let dont_inst = self.get_dont_instruction();
 
-            match (do_inst, dont_inst) {
-                // Some and None don't matter because we can't have other instruction later anyways
-                (Some(do_inst), Some(dont_inst)) => {
-                    // do starts like dont, so only if we have dont = false and do = true we should enable
-                    if do_inst && !dont_inst {
-                        self.is_enabled = true
-                    } else if dont_inst {
-                        self.is_enabled = false;
-                    }
+            // Some and None don't matter because we can't have other instruction later anyways
+            if let (Some(do_inst), Some(dont_inst)) = (do_inst, dont_inst) {
+                // do starts like dont, so only if we have dont = false and do = true we should enable
+                if do_inst && !dont_inst {
+                    self.is_enabled = true
+                } else if dont_inst {
+                    self.is_enabled = false;
                 }
-                _ => (),
             }
 
             if let Some((result, new_cursor)) = self.try_get_single_instruction() {
@@ -146,29 +156,87 @@ impl<'a> Iterator for InstructionParser<'a> {
     }
 }
 
-pub fn part1(input: &str) -> u32 {
-    let parser = InstructionParser::new(input, false);
-    parser.into_iter().sum()
+/// The instructions are parsed lazily while solving, so we only check the input is ascii
+pub fn parse(input: &str) -> Result<String, ParseError> {
+    for (line_number, line) in input.lines().enumerate() {
+        if let Some((column, c)) = line.chars().enumerate().find(|(_, c)| !c.is_ascii()) {
+            return Err(ParseError::new(
+                line_number + 1,
+                column + 1,
+                format!("unexpected non ascii character `{c}`"),
+            ));
+        }
+    }
+
+    Ok(input.to_owned())
+}
+
+fn sum_instructions(mut parser: InstructionParser) -> Result<u32, SolveError> {
+    let mut total = 0;
+    while let Some(product) = parser.next() {
+        total = checked::add(total, product).map_err(|e| e.on_line(parser.line()))?;
+    }
+
+    Ok(total)
+}
+
+pub fn part1(input: &str) -> Result<u32, SolveError> {
+    sum_instructions(InstructionParser::new(input, false))
 }
 
-pub fn part2(input: &str) -> u32 {
-    let parser = InstructionParser::new(input, true);
-    parser.into_iter().sum()
+pub fn part2(input: &str) -> Result<u32, SolveError> {
+    sum_instructions(InstructionParser::new(input, true))
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = String;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const INPUT: &str = include_str!("../inputs/day03.example.txt");
+
     #[test]
     fn test_part1() {
         let input = r#"xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"#;
-        assert_eq!(part1(input), 161);
+        assert_eq!(part1(&parse(input).unwrap()), Ok(161));
     }
 
     #[test]
     fn test_part2() {
-        let input = r#"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))s"#;
-        assert_eq!(part2(input), 48);
+        assert_eq!(part2(&parse(INPUT).unwrap()), Ok(48));
+    }
+
+    #[test]
+    fn test_parse_non_ascii() {
+        let error = parse("mul(1,2)\nmul(2,3)é").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 9));
+    }
+
+    #[test]
+    fn test_instruction_parser_non_ascii() {
+        // found by the `day3_instructions` fuzz target
+        let products: Vec<u32> = InstructionParser::new("-\u{192}mul(2,3)", false).collect();
+        assert_eq!(products, vec![6]);
     }
 }
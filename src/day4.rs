@@ -1,138 +1,121 @@
-/// Always assume the string is valid ascii
-pub fn part1(input: &str) -> u32 {
-    let mut grid = Vec::new();
-    for line in input.lines() {
-        grid.push(line.as_bytes());
-    }
+use crate::{
+    checked,
+    error::{ParseError, SolveError},
+    grid::{Direction, Grid, Point},
+    solution::Solution,
+};
 
-    let mut sum = 0;
-    for line in &grid {
-        for idx in 0..line.len() - 3 {
-            if &line[idx..idx + 4] == b"XMAS" || &line[idx..idx + 4] == b"SAMX" {
-                sum += 1;
-            }
+pub fn parse(input: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse(input, |c| {
+        if c.is_ascii() {
+            Ok(c as u8)
+        } else {
+            Err(format!("unexpected non ascii character `{c}`"))
         }
-    }
+    })
+}
 
-    let num_rows = grid.len();
-    let num_columns = grid[0].len();
-
-    for row in 0..num_rows {
-        for column in 0..num_columns {
-            if row + 3 < num_rows {
-                if (grid[row][column] == b'X'
-                    && grid[row + 1][column] == b'M'
-                    && grid[row + 2][column] == b'A'
-                    && grid[row + 3][column] == b'S')
-                    || (grid[row][column] == b'S'
-                        && grid[row + 1][column] == b'A'
-                        && grid[row + 2][column] == b'M'
-                        && grid[row + 3][column] == b'X')
-                {
-                    sum += 1;
-                }
-            }
+/// Whether `word` is spelled starting at `start` and going in `direction`
+fn spells(grid: &Grid<u8>, word: &[u8], start: Point, direction: Direction) -> bool {
+    let mut current = Some(start);
+    for letter in word {
+        match current {
+            Some(point) if grid[point] == *letter => current = grid.step(point, direction),
+            _ => return false,
         }
     }
 
-    for row in 0..num_rows - 3 {
-        for column in 0..num_columns - 3 {
-            if (grid[row][column] == b'X'
-                && grid[row + 1][column + 1] == b'M'
-                && grid[row + 2][column + 2] == b'A'
-                && grid[row + 3][column + 3] == b'S')
-                || (grid[row][column] == b'S'
-                    && grid[row + 1][column + 1] == b'A'
-                    && grid[row + 2][column + 2] == b'M'
-                    && grid[row + 3][column + 3] == b'X')
-            {
-                sum += 1;
-            }
-        }
-    }
+    true
+}
 
-    for row in (3..num_rows).rev() {
-        for column in 0..num_columns - 3 {
-            if (grid[row][column] == b'X'
-                && grid[row - 1][column + 1] == b'M'
-                && grid[row - 2][column + 2] == b'A'
-                && grid[row - 3][column + 3] == b'S')
-                || (grid[row][column] == b'S'
-                    && grid[row - 1][column + 1] == b'A'
-                    && grid[row - 2][column + 2] == b'M'
-                    && grid[row - 3][column + 3] == b'X')
-            {
-                sum += 1;
-            }
-        }
-    }
+/// Count XMAS in all 8 directions. Starting only from the X makes sure we
+/// count every word once, and not again when reading it backwards
+pub fn part1(grid: &Grid<u8>) -> Result<u32, SolveError> {
+    checked::narrow(
+        grid.points()
+            .map(|start| {
+                Direction::ALL
+                    .into_iter()
+                    .filter(|direction| spells(grid, b"XMAS", start, *direction))
+                    .count()
+            })
+            .sum(),
+    )
+}
 
-    sum
+/// Count the As that are the middle of 2 diagonal MAS in the shape of an X
+pub fn part2(grid: &Grid<u8>) -> Result<u32, SolveError> {
+    let count = grid
+        .points()
+        .filter(|center| grid[*center] == b'A')
+        .filter(|center| {
+            // each diagonal must be a MAS going through the A, either forwards or backwards
+            [Direction::UpLeft, Direction::UpRight]
+                .into_iter()
+                .all(|direction| {
+                    let Some(start) = grid.step(*center, direction) else {
+                        return false;
+                    };
+                    let direction = direction.opposite();
+                    spells(grid, b"MAS", start, direction) || spells(grid, b"SAM", start, direction)
+                })
+        })
+        .count();
+    checked::narrow(count)
 }
 
-pub fn part2(input: &str) -> u32 {
-    let mut grid = Vec::new();
-    for line in input.lines() {
-        grid.push(line.as_bytes());
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = Grid<u8>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
     }
 
-    let num_rows = grid.len();
-    let num_columns = grid[0].len();
-
-    let mut sum = 0;
-    for row in 1..num_rows - 1 {
-        for column in 1..num_columns - 1 {
-            if grid[row][column] != b'A' {
-                continue;
-            }
-
-            if (grid[row - 1][column - 1] == b'M'
-                && grid[row + 1][column - 1] == b'M'
-                && grid[row + 1][column + 1] == b'S'
-                && grid[row - 1][column + 1] == b'S')
-                || (grid[row - 1][column - 1] == b'S'
-                    && grid[row + 1][column - 1] == b'M'
-                    && grid[row + 1][column + 1] == b'M'
-                    && grid[row - 1][column + 1] == b'S')
-                || (grid[row - 1][column - 1] == b'S'
-                    && grid[row + 1][column - 1] == b'S'
-                    && grid[row + 1][column + 1] == b'M'
-                    && grid[row - 1][column + 1] == b'M')
-                || (grid[row - 1][column - 1] == b'M'
-                    && grid[row + 1][column - 1] == b'S'
-                    && grid[row + 1][column + 1] == b'S'
-                    && grid[row - 1][column + 1] == b'M')
-            {
-                sum += 1;
-            }
-        }
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError> {
+        part1(input)
     }
 
-    sum
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = r#"MMMSXXMASM
-MSAMXMSMSA
-AMXSXMAAMM
-MSAMASMSMX
-XMASAMXAMM
-XXAMMXXAMA
-SMSMSASXSS
-SAXAMASAAA
-MAMMMXMMMM
-MXMXAXMASX"#;
+    const INPUT: &str = include_str!("../inputs/day04.example.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 18);
+        assert_eq!(part1(&parse(INPUT).unwrap()), Ok(18));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 9);
+        assert_eq!(part2(&parse(INPUT).unwrap()), Ok(9));
+    }
+
+    #[test]
+    fn test_small_grids() {
+        assert_eq!(part1(&parse("XMAS").unwrap()), Ok(1));
+        assert_eq!(part1(&parse("X\nM\nA").unwrap()), Ok(0));
+        assert_eq!(part2(&parse("A").unwrap()), Ok(0));
+        assert_eq!(part2(&parse("").unwrap()), Ok(0));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("XMAS\nXMA\nXMAS").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+
+        let error = parse("XMAS\nXMASS").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
     }
 }
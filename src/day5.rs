@@ -1,55 +1,56 @@
 //! Solve day5
 //!
-//! Of course as per usual everything can fail, but here we know the input is valid
-//! and it's just less code to deal with errors :)
-use std::{convert::Infallible, str::FromStr};
+//! The single line parsers report errors on line 1, and [`SafetyManual`] moves
+//! them to the line they actually came from.
+use std::{collections::HashSet, str::FromStr};
+
+use crate::{
+    checked,
+    error::{self, ParseError, SolveError},
+    solution::Solution,
+};
 
 #[derive(Debug)]
 pub struct OrderingRule {
-    before: u8,
-    after: u8,
+    pub before: u64,
+    pub after: u64,
 }
 
 impl FromStr for OrderingRule {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut it = s.split("|").fuse();
-        let before = it.next().unwrap().parse().unwrap();
-        let after = it.next().unwrap().parse().unwrap();
+        let Some((before, after)) = s.split_once('|') else {
+            return Err(ParseError::end_of_input(s, "expected `|` between 2 pages"));
+        };
+        let before = error::parse_number(1, s, before)?;
+        let after = error::parse_number(1, s, after)?;
         Ok(Self { before, after })
     }
 }
 
 /// Ordering rules in a more efficient storage for faster lookup
 ///
-/// Each rules consists of a `u8` number that must come before another `u8` number
-/// For faster lookup we convert each rule into a single number by taking the before and multiplying by 256
-/// This way we get a new `u16`. Now to check if a rule exists for 2 numbers we need to do the same conversion and check
-/// if it exists in our rules by a simple index lookup
+/// Each rule is a page number that must come before another page number, stored
+/// as a `(before, after)` pair so checking if a rule exists is a single hash lookup.
+/// It only grows with the number of rules, no matter how large the page numbers are.
 #[derive(Debug)]
 pub struct OrderingRules {
-    // can probably be more efficient with a bitvec but whatevs
-    rules: [bool; u16::MAX as usize + 1],
+    rules: HashSet<(u64, u64)>,
 }
 
 impl OrderingRules {
     fn from_ordering_rules_slice(ordering_rules: &[OrderingRule]) -> Self {
-        let mut rules = [false; u16::MAX as usize + 1];
-        for rule in ordering_rules {
-            rules[Self::convert_ordering_rule(&rule)] = true;
-        }
+        let rules = ordering_rules
+            .iter()
+            .map(|rule| (rule.before, rule.after))
+            .collect();
 
         Self { rules }
     }
 
-    fn convert_ordering_rule(rule: &OrderingRule) -> usize {
-        rule.before as usize * 256 + rule.after as usize
-    }
-
-    fn exists(&self, rule: &OrderingRule) -> bool {
-        let index = Self::convert_ordering_rule(rule);
-        self.rules[index]
+    pub fn exists(&self, rule: &OrderingRule) -> bool {
+        self.rules.contains(&(rule.before, rule.after))
     }
 }
 
@@ -61,21 +62,44 @@ impl From<Vec<OrderingRule>> for OrderingRules {
 
 #[derive(Debug, Clone)]
 pub struct PageUpdate {
-    pages: Vec<u8>,
+    pages: Vec<u64>,
+    /// Line of the input, to blame it when the answer overflows
+    line: usize,
 }
 
 impl FromStr for PageUpdate {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let pages = s.split(",").map(|x| x.parse().unwrap()).collect();
-        Ok(Self { pages })
+        let pages = s
+            .split(',')
+            .map(|page| error::parse_number(1, s, page))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { pages, line: 1 })
     }
 }
 
-fn convert_update_to_right_order(update: PageUpdate, ordering_rules: &OrderingRules) -> PageUpdate {
+/// Swap pages until the update is in the right order
+///
+/// Every swap fixes a pair in the wrong order, so rules without cycles need fewer
+/// swaps than there are pairs of pages. Any more than that and the rules contradict
+/// each other, like `1|2` and `2|1`.
+fn convert_update_to_right_order(
+    update: PageUpdate,
+    ordering_rules: &OrderingRules,
+) -> Result<PageUpdate, SolveError> {
     let mut current_update = update;
+    let max_swaps = current_update.pages.len().pow(2);
+    let mut swaps = 0;
     while !current_update.is_in_right_order(ordering_rules) {
+        if swaps == max_swaps {
+            return Err(
+                SolveError::new("the ordering rules of these pages have a cycle")
+                    .on_line(current_update.line),
+            );
+        }
+        swaps += 1;
+
         let mut to_flip = None;
         for (i, before) in current_update.pages.iter().enumerate() {
             for (j, after) in current_update.pages.iter().skip(i).enumerate() {
@@ -96,11 +120,15 @@ fn convert_update_to_right_order(update: PageUpdate, ordering_rules: &OrderingRu
         }
     }
 
-    current_update
+    Ok(current_update)
 }
 
 impl PageUpdate {
-    fn is_in_right_order(&self, ordering_rules: &OrderingRules) -> bool {
+    pub fn pages(&self) -> &[u64] {
+        &self.pages
+    }
+
+    pub fn is_in_right_order(&self, ordering_rules: &OrderingRules) -> bool {
         for (i, before) in self.pages.iter().enumerate() {
             for after in self.pages.iter().skip(i) {
                 let opposite_rule = OrderingRule {
@@ -117,14 +145,24 @@ impl PageUpdate {
         true
     }
 
-    fn middle(&self) -> u8 {
+    pub fn middle(&self) -> u64 {
         self.pages[self.pages.len() / 2]
     }
 
+    fn on_line(self, line: usize) -> Self {
+        Self { line, ..self }
+    }
+
     /// Assume we have an incorrect order and fix it. This clones the object
-    fn convert_to_right_order(&self, ordering_rules: &OrderingRules) -> Self {
+    ///
+    /// Fails when the rules have a cycle, so there is no right order.
+    pub fn convert_to_right_order(
+        &self,
+        ordering_rules: &OrderingRules,
+    ) -> Result<Self, SolveError> {
         let update = Self {
             pages: self.pages.clone(),
+            line: self.line,
         };
         convert_update_to_right_order(update, ordering_rules)
     }
@@ -136,22 +174,39 @@ pub struct SafetyManual {
     update: Vec<PageUpdate>,
 }
 
+impl SafetyManual {
+    pub fn ordering_rules(&self) -> &OrderingRules {
+        &self.ordering_rules
+    }
+
+    pub fn updates(&self) -> &[PageUpdate] {
+        &self.update
+    }
+}
+
 impl FromStr for SafetyManual {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parsing_ordering = true;
         let mut ordering_rules = Vec::new();
         let mut update = Vec::new();
-        for line in s.lines() {
-            if line == "" {
+        for (line_number, line) in error::lines(s) {
+            if line.is_empty() {
                 parsing_ordering = false;
                 continue;
             }
             if parsing_ordering {
-                ordering_rules.push(line.parse().unwrap());
+                ordering_rules.push(
+                    line.parse()
+                        .map_err(|e: ParseError| e.on_line(line_number))?,
+                );
             } else {
-                update.push(line.parse().unwrap());
+                update.push(
+                    line.parse()
+                        .map(|update: PageUpdate| update.on_line(line_number))
+                        .map_err(|e: ParseError| e.on_line(line_number))?,
+                );
             }
         }
 
@@ -162,72 +217,131 @@ impl FromStr for SafetyManual {
     }
 }
 
-pub fn part1(input: &str) -> u32 {
-    let manual: SafetyManual = input.parse().unwrap();
+pub fn parse(input: &str) -> Result<SafetyManual, ParseError> {
+    input.parse()
+}
+
+/// Sum the middle pages, blaming the update that made the sum overflow
+fn sum_middles<'a>(
+    mut updates: impl Iterator<Item = Result<(&'a PageUpdate, u64), SolveError>>,
+) -> Result<u64, SolveError> {
+    updates.try_fold(0, |total, update| {
+        let (update, middle) = update?;
+        checked::add(total, middle).map_err(|e| e.on_line(update.line))
+    })
+}
+
+pub fn part1(manual: &SafetyManual) -> Result<u64, SolveError> {
+    sum_middles(
+        manual
+            .update
+            .iter()
+            .filter(|update| update.is_in_right_order(&manual.ordering_rules))
+            .map(|update| Ok((update, update.middle()))),
+    )
+}
 
-    manual
-        .update
-        .iter()
-        .filter(|update| update.is_in_right_order(&manual.ordering_rules))
-        .map(|update| update.middle() as u32)
-        .sum()
+pub fn part2(manual: &SafetyManual) -> Result<u64, SolveError> {
+    sum_middles(
+        manual
+            .update
+            .iter()
+            .filter(|update| !update.is_in_right_order(&manual.ordering_rules))
+            .map(|update| {
+                let middle = update
+                    .convert_to_right_order(&manual.ordering_rules)?
+                    .middle();
+                Ok((update, middle))
+            }),
+    )
 }
 
-pub fn part2(input: &str) -> u32 {
-    let manual: SafetyManual = input.parse().unwrap();
+pub struct Day5;
 
-    manual
-        .update
-        .iter()
-        .filter(|update| !update.is_in_right_order(&manual.ordering_rules))
-        .map(|update| {
-            update
-                .convert_to_right_order(&manual.ordering_rules)
-                .middle() as u32
-        })
-        .sum()
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = SafetyManual;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = r#"47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
-
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47"#;
+    const INPUT: &str = include_str!("../inputs/day05.example.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 143);
+        assert_eq!(part1(&parse(INPUT).unwrap()), Ok(143));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 123);
+        assert_eq!(part2(&parse(INPUT).unwrap()), Ok(123));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("47|53\n97-13\n\n75,47").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 6));
+
+        let error = parse("47|53\n\n75,47,,61").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 7));
+
+        let error = parse("47|53\n\n75,99999999999999999999").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 4));
+    }
+
+    #[test]
+    fn test_large_pages() {
+        let manual = parse("1000|70000\n\n1000,300,70000\n70000,1000,300").unwrap();
+        assert_eq!(part1(&manual), Ok(300));
+        assert_eq!(part2(&manual), Ok(70000));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        let manual = parse(
+            "1|2
+
+18446744073709551615
+2,18446744073709551615,1
+1,2,3
+2,5,1",
+        )
+        .unwrap();
+        assert_eq!(part1(&manual).unwrap_err().line, Some(5));
+        assert_eq!(part2(&manual).unwrap_err().line, Some(6));
+    }
+
+    #[test]
+    fn test_cyclic_rules() {
+        let manual = parse("1|2\n2|1\n\n3,4,5\n1,2,3\n").unwrap();
+        assert_eq!(part1(&manual), Ok(4));
+        assert_eq!(part2(&manual).unwrap_err().line, Some(5));
+
+        let manual = parse("1|1\n\n1,2,3\n").unwrap();
+        assert_eq!(part2(&manual).unwrap_err().line, Some(3));
+    }
+
+    #[test]
+    fn test_parse_crlf() {
+        let manual = parse(&INPUT.replace('\n', "\r\n")).unwrap();
+        assert_eq!(part1(&manual), Ok(143));
     }
 }
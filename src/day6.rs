@@ -1,19 +1,31 @@
-use std::{collections::HashSet, convert::Infallible, fmt::Write, str::FromStr};
+use std::{collections::HashSet, fmt::Write, str::FromStr};
+
+use crate::{
+    checked,
+    error::{ParseError, SolveError},
+    grid::{self, Direction, Point},
+    solution::Solution,
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum MapItem {
+pub enum MapItem {
     Guard,
     Obstruction,
     Empty,
 }
 
-impl From<char> for MapItem {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for MapItem {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '.' => Self::Empty,
-            '#' => Self::Obstruction,
-            '^' => Self::Guard,
-            i => panic!("invalid map item: {i}"),
+            '.' => Ok(Self::Empty),
+            '#' => Ok(Self::Obstruction),
+            '^' => Ok(Self::Guard),
+            i => Err(format!("invalid map item `{i}`")),
         }
     }
 }
@@ -28,106 +40,77 @@ impl std::fmt::Display for MapItem {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    Left = 0,
-    Right = 1,
-    Up = 2,
-    Down = 3,
-}
-
 #[derive(Debug, Clone)]
-struct Grid {
+pub struct Grid {
     // If we want to be more efficient, we can change this to be slice of slice
     // that way clones are cheap
-    rows: Vec<Vec<MapItem>>,
-    guard_position: (usize, usize),
+    map: grid::Grid<MapItem>,
+    guard_position: Point,
     guard_direction: Direction,
 }
 
 impl std::fmt::Display for Grid {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in &self.rows {
-            for item in row {
-                write!(f, "{}", item)?;
-            }
-            writeln!(f)?;
-        }
-        writeln!(f)
+        writeln!(f, "{}", self.map)
     }
 }
 
 impl FromStr for Grid {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut rows = Vec::new();
-        let mut guard_start_position = None;
-        for (row_index, line) in s.lines().enumerate() {
-            let mut row = Vec::with_capacity(line.len());
-            for (column, c) in line.chars().enumerate() {
-                let map_item: MapItem = c.into();
-                if map_item == MapItem::Guard {
-                    guard_start_position = Some((row_index, column));
-                }
-                row.push(map_item);
+        let map = grid::Grid::parse(s, MapItem::try_from)?;
+        let guards: Vec<Point> = map
+            .iter()
+            .filter(|(_, item)| **item == MapItem::Guard)
+            .map(|(point, _)| point)
+            .take(2)
+            .collect();
+        let guard_position = match guards[..] {
+            [guard] => guard,
+            [] => return Err(ParseError::end_of_input(s, "the map has no guard `^`")),
+            [_, second, ..] => {
+                return Err(ParseError::new(
+                    second.row + 1,
+                    second.column + 1,
+                    "found a second guard",
+                ))
             }
-
-            rows.push(row);
-        }
+        };
 
         Ok(Self {
-            rows,
-            guard_position: guard_start_position.unwrap(),
+            map,
+            guard_position,
             guard_direction: Direction::Up,
         })
     }
 }
 
 impl Grid {
-    fn move_guard(&mut self) -> bool {
-        let guard_next = match self.guard_direction {
-            Direction::Left => {
-                if self.guard_position.1 == 0 {
-                    return false;
-                }
-                (self.guard_position.0, self.guard_position.1 - 1)
-            }
-            Direction::Right => {
-                if self.guard_position.1 == self.rows[0].len() - 1 {
-                    return false;
-                }
-                (self.guard_position.0, self.guard_position.1 + 1)
-            }
-            Direction::Up => {
-                if self.guard_position.0 == 0 {
-                    return false;
-                }
-                (self.guard_position.0 - 1, self.guard_position.1)
-            }
-            Direction::Down => {
-                if self.guard_position.0 == self.rows.len() - 1 {
-                    return false;
-                }
-                (self.guard_position.0 + 1, self.guard_position.1)
-            }
+    pub fn guard_position(&self) -> Point {
+        self.guard_position
+    }
+
+    pub fn guard_direction(&self) -> Direction {
+        self.guard_direction
+    }
+
+    /// Move the guard a single step, or turn if there is an obstruction ahead.
+    /// Returns false once the guard leaves the map
+    pub fn move_guard(&mut self) -> bool {
+        let Some(guard_next) = self.map.step(self.guard_position, self.guard_direction) else {
+            return false;
         };
-        let next_map_item = self.rows[guard_next.0][guard_next.1];
-        match next_map_item {
+        match self.map[guard_next] {
             MapItem::Guard => panic!("impossible"),
             MapItem::Obstruction => {
                 // turn 90 degress
-                self.guard_direction = match self.guard_direction {
-                    Direction::Left => Direction::Up,
-                    Direction::Right => Direction::Down,
-                    Direction::Up => Direction::Right,
-                    Direction::Down => Direction::Left,
-                };
+                self.guard_direction = self.guard_direction.turn_right();
             }
             MapItem::Empty => {
-                self.rows[self.guard_position.0][self.guard_position.1] = MapItem::Empty;
+                self.map[self.guard_position] = MapItem::Empty;
                 self.guard_position = guard_next;
-                self.rows[self.guard_position.0][self.guard_position.1] = MapItem::Guard;
+                self.map[self.guard_position] = MapItem::Guard;
             }
         }
 
@@ -135,105 +118,152 @@ impl Grid {
     }
 }
 
-pub fn part1(input: &str) -> u32 {
-    let mut grid: Grid = input.parse().unwrap();
+pub fn parse(input: &str) -> Result<Grid, ParseError> {
+    input.parse()
+}
+
+/// Positions the guard walks on before leaving the map, including where they start
+///
+/// Fails when the guard walks in circles forever instead, the puzzle only has
+/// guards that leave.
+pub fn walk(grid: &Grid) -> Result<HashSet<Point>, SolveError> {
+    let mut grid = grid.clone();
+    let start = grid.guard_position;
     // yes we can be more efficient by having a custom "hash" function
-    let mut locations = HashSet::new();
-    locations.insert(grid.guard_position);
-    while grid.move_guard() {
+    let mut locations = HashSet::from([start]);
+    let mut states = HashSet::new();
+    while states.insert((grid.guard_position, grid.guard_direction)) {
+        if !grid.move_guard() {
+            return Ok(locations);
+        }
         locations.insert(grid.guard_position);
     }
 
-    locations.len() as u32
+    Err(
+        SolveError::new("the guard walks in circles and never leaves the map")
+            .on_line(start.row + 1),
+    )
+}
+
+pub fn part1(grid: &Grid) -> Result<u32, SolveError> {
+    checked::narrow(walk(grid)?.len())
+}
+
+/// Index of the guard's state in the visited table, the guard only walks in 4 directions
+fn state_index(grid: &Grid) -> usize {
+    let point = grid.guard_position;
+    (point.row * grid.map.width() + point.column) * 4 + grid.guard_direction.index() / 2
 }
 
-fn try_find_single_loop(grid: &Grid, position: (usize, usize)) -> bool {
+fn try_find_single_loop(grid: &Grid, position: Point) -> bool {
     let mut cloned_grid = grid.clone();
     // after looking at flamegraph it looks like most of the time is spent in
     // hash, so we try to be more efficient.
     // we know the max rows and columns and that there are 4 directions
-    // a custom "hash" can be row * max_rows * max_columns + columns * max_columns + direction
-    let num_rows = cloned_grid.rows.len();
-    let num_columns = cloned_grid.rows[0].len();
+    // a custom "hash" can be (row * max_columns + column) * 4 + direction
     let num_directions = 4;
     // funny thing is that now most of the time is allocation, which we can also remove by only allocating
     // this once and just clearing it every run probably
     // but for now good enough because it finishes in like 2 seconds in debug and 1 in release
     // still not that fast but ok
     let mut current_locations =
-        vec![false; ((num_rows + 1) * (num_columns + 1)) * (num_columns + 1) * num_directions];
-    current_locations[cloned_grid.guard_position.0 * num_rows * num_columns
-        + cloned_grid.guard_position.1 * num_columns
-        + cloned_grid.guard_direction as usize] = true;
-    cloned_grid.rows[position.0][position.1] = MapItem::Obstruction;
+        vec![false; cloned_grid.map.width() * cloned_grid.map.height() * num_directions];
+    current_locations[state_index(&cloned_grid)] = true;
+    cloned_grid.map[position] = MapItem::Obstruction;
 
     while cloned_grid.move_guard() {
-        if current_locations[cloned_grid.guard_position.0 * num_rows * num_columns
-            + cloned_grid.guard_position.1 * num_columns
-            + cloned_grid.guard_direction as usize]
-        {
+        let index = state_index(&cloned_grid);
+        if current_locations[index] {
             // found a loop
             return true;
         }
-        current_locations[cloned_grid.guard_position.0 * num_rows * num_columns
-            + cloned_grid.guard_position.1 * num_columns
-            + cloned_grid.guard_direction as usize] = true;
+        current_locations[index] = true;
     }
 
     // exited without a loop
     false
 }
 
-pub fn part2(input: &str) -> u32 {
-    let grid: Grid = input.parse().unwrap();
-    // first we find all locations and direction the guard moved without adding objects
-    let mut locations = HashSet::new();
+pub fn part2(grid: &Grid) -> Result<u32, SolveError> {
+    // first we find all locations the guard moved to without adding objects
+    let locations = walk(grid)?;
     let initial_position = grid.guard_position;
 
-    let mut cloned_grid = grid.clone();
+    // now we try to switch each position he walked at with an obstacle and see if we get a loop
+    #[cfg(feature = "parallel")]
+    let locations = locations.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let locations = locations.into_iter();
 
-    while cloned_grid.move_guard() {
-        locations.insert(cloned_grid.guard_position);
-    }
+    let count = locations
+        // can't place an obstacle in the intial position
+        .filter(|position| *position != initial_position)
+        .filter(|position| try_find_single_loop(grid, *position))
+        .count();
+    checked::narrow(count)
+}
 
-    let mut num_options = 0;
-    // now we try to switch each position he walked at with an obstacle and see if we get a loop
-    for position in locations {
-        if position == initial_position {
-            // can't place an obstacle in the intial position
-            continue;
-        }
+pub struct Day6;
 
-        if try_find_single_loop(&grid, position) {
-            num_options += 1;
-        }
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = Grid;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError> {
+        part1(input)
     }
 
-    num_options
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = r##"....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#..."##;
+    const INPUT: &str = include_str!("../inputs/day06.example.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 41);
+        assert_eq!(part1(&parse(INPUT).unwrap()), Ok(41));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 6);
+        assert_eq!(part2(&parse(INPUT).unwrap()), Ok(6));
+    }
+
+    #[test]
+    fn test_guard_never_leaves() {
+        let grid = parse(".#.\n#^#\n.#.\n").unwrap();
+        assert_eq!(part1(&grid).unwrap_err().line, Some(2));
+        assert_eq!(part2(&grid).unwrap_err().line, Some(2));
+
+        // walks around the square in the middle
+        let grid = parse(".#..\n.^.#\n#...\n..#.\n").unwrap();
+        assert_eq!(part1(&grid).unwrap_err().line, Some(2));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("..#\n.x^").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+
+        let error = parse("..#\n.^\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let error = parse("..#\n...\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+
+        let error = parse("..^\n.^.").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
     }
 }
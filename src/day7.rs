@@ -1,5 +1,14 @@
-use itertools::{repeat_n, Itertools};
-use std::{convert::Infallible, str::FromStr};
+use itertools::Itertools;
+use std::str::FromStr;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    checked,
+    error::{self, ParseError, SolveError},
+    solution::Solution,
+};
 
 #[derive(Debug, Clone, Copy)]
 enum Op {
@@ -8,120 +17,214 @@ enum Op {
     Concat,
 }
 
+#[derive(Debug)]
 pub struct Equation {
     result: u64,
     numbers: Vec<u16>,
+    /// Line of the input, to blame it when the answer overflows
+    line: usize,
 }
 
 impl FromStr for Equation {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut it = s.split(": ").fuse();
-        let result = it.next().unwrap().parse().unwrap();
-        let numbers = it
-            .next()
-            .unwrap()
+        let Some((result, numbers)) = s.split_once(": ") else {
+            return Err(ParseError::end_of_input(
+                s,
+                "expected `: ` after the result",
+            ));
+        };
+        let result = error::parse_number(1, s, result)?;
+        let numbers: Vec<u16> = numbers
             .split_whitespace()
-            .map(|num| num.parse().unwrap())
-            .collect();
-        Ok(Self { result, numbers })
+            .map(|num| error::parse_number(1, s, num))
+            .collect::<Result<_, _>>()?;
+        if numbers.is_empty() {
+            return Err(ParseError::end_of_input(s, "expected at least 1 number"));
+        }
+        Ok(Self {
+            result,
+            numbers,
+            line: 1,
+        })
     }
 }
 
 impl Equation {
-    fn is_satisfiable(&self) -> bool {
-        for it in (0..self.numbers.len() - 1)
-            .map(|_| [Op::Add, Op::Mul])
-            .multi_cartesian_product()
-        {
-            let result =
-                it.iter()
-                    .enumerate()
-                    .fold(self.numbers[0] as u64, |acc, (i, op)| match op {
-                        Op::Mul => acc as u64 * self.numbers[i + 1] as u64,
-                        Op::Add => acc as u64 + self.numbers[i + 1] as u64,
-                        Op::Concat => panic!("impossible"),
-                    });
-            if result == self.result {
-                return true;
+    pub fn result(&self) -> u64 {
+        self.result
+    }
+
+    pub fn numbers(&self) -> &[u16] {
+        &self.numbers
+    }
+
+    fn on_line(self, line: usize) -> Self {
+        Self { line, ..self }
+    }
+
+    /// Evaluate the numbers left to right with the operators in between
+    ///
+    /// `None` means the value went past the result, or past `u64`, so these operators
+    /// don't match. Adding and concatenating never make a value smaller, only
+    /// multiplying by 0 brings it back down.
+    fn evaluate(&self, ops: &[Op]) -> Option<u64> {
+        let fits = |value: &u64| *value <= self.result;
+        let mut acc = Some(self.numbers[0] as u64).filter(fits);
+        for (op, number) in ops.iter().zip(&self.numbers[1..]) {
+            let number = *number as u64;
+            acc = match (op, acc) {
+                (Op::Mul, _) if number == 0 => Some(0),
+                (_, None) => None,
+                (Op::Mul, Some(acc)) => acc.checked_mul(number),
+                (Op::Add, Some(acc)) => acc.checked_add(number),
+                (Op::Concat, Some(acc)) => {
+                    let num_digits = number.checked_ilog10().map_or(1, |log10| log10 + 1);
+                    10_u64
+                        .checked_pow(num_digits)
+                        .and_then(|shift| acc.checked_mul(shift))
+                        .and_then(|shifted| shifted.checked_add(number))
+                }
             }
+            .filter(fits);
         }
 
-        false
+        acc
     }
 
-    fn is_satisfiable_with_concat(&self) -> bool {
-        for it in (0..self.numbers.len() - 1)
-            .map(|_| [Op::Add, Op::Mul, Op::Concat])
+    pub fn is_satisfiable(&self) -> bool {
+        (0..self.numbers.len() - 1)
+            .map(|_| [Op::Add, Op::Mul])
             .multi_cartesian_product()
-        {
-            let result =
-                it.iter()
-                    .enumerate()
-                    .fold(self.numbers[0] as u64, |acc, (i, op)| match op {
-                        Op::Mul => acc as u64 * self.numbers[i + 1] as u64,
-                        Op::Add => acc as u64 + self.numbers[i + 1] as u64,
-                        Op::Concat => {
-                            let log10 = (self.numbers[i + 1] as f64).log10();
-                            let mut num_digits = log10.ceil() as u64;
-
-                            if log10 as u64 == num_digits {
-                                num_digits += 1;
-                            }
-
-                            acc as u64 * 10_u64.pow((num_digits).try_into().unwrap())
-                                + self.numbers[i + 1] as u64
-                        }
-                    });
-            if result == self.result {
-                return true;
-            }
-        }
+            .any(|ops| self.evaluate(&ops) == Some(self.result))
+    }
 
-        false
+    pub fn is_satisfiable_with_concat(&self) -> bool {
+        (0..self.numbers.len() - 1)
+            .map(|_| [Op::Add, Op::Mul, Op::Concat])
+            .multi_cartesian_product()
+            .any(|ops| self.evaluate(&ops) == Some(self.result))
     }
 }
 
-pub fn part1(input: &str) -> u64 {
-    let equations: Vec<Equation> = input.lines().map(|line| line.parse().unwrap()).collect();
-    equations
-        .iter()
-        .filter(|eq| eq.is_satisfiable())
-        .map(|x| x.result)
-        .sum()
+pub fn parse(input: &str) -> Result<Vec<Equation>, ParseError> {
+    error::lines(input)
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line_number, line)| {
+            line.parse()
+                .map(|equation: Equation| equation.on_line(line_number))
+                .map_err(|e: ParseError| e.on_line(line_number))
+        })
+        .collect()
+}
+
+/// Sum the results, blaming the equation that made the sum overflow
+fn sum_results(equations: &[&Equation]) -> Result<u64, SolveError> {
+    equations.iter().try_fold(0, |total, eq| {
+        checked::add(total, eq.result).map_err(|e| e.on_line(eq.line))
+    })
+}
+
+pub fn part1(equations: &[Equation]) -> Result<u64, SolveError> {
+    #[cfg(feature = "parallel")]
+    let equations = equations.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let equations = equations.iter();
+
+    // collecting keeps the order of the input, so an overflow blames the same line every run
+    let satisfiable: Vec<_> = equations.filter(|eq| eq.is_satisfiable()).collect();
+    sum_results(&satisfiable)
 }
 
-pub fn part2(input: &str) -> u64 {
-    let equations: Vec<Equation> = input.lines().map(|line| line.parse().unwrap()).collect();
-    equations
-        .iter()
+pub fn part2(equations: &[Equation]) -> Result<u64, SolveError> {
+    #[cfg(feature = "parallel")]
+    let equations = equations.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let equations = equations.iter();
+
+    let satisfiable: Vec<_> = equations
         .filter(|eq| eq.is_satisfiable_with_concat())
-        .map(|x| x.result)
-        .sum()
+        .collect();
+    sum_results(&satisfiable)
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = Vec<Equation>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = r#"190: 10 19
-3267: 81 40 27
-83: 17 5
-156: 15 6
-7290: 6 8 6 15
-161011: 16 10 13
-192: 17 8 14
-21037: 9 7 18 13
-292: 11 6 16 20"#;
+    const INPUT: &str = include_str!("../inputs/day07.example.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 3749);
+        assert_eq!(part1(&parse(INPUT).unwrap()), Ok(3749));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 11387);
+        assert_eq!(part2(&parse(INPUT).unwrap()), Ok(11387));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("190: 10 19\n3267 81 40").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 11));
+
+        let error = parse("190: 10 x19").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 9));
+
+        let error = parse("190: ").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+    }
+
+    #[test]
+    fn test_operators_past_u64() {
+        // multiplying all of them overflows, which only rules out that combination
+        let equations = parse(
+            "190: 10 19
+1: 65535 65535 65535 65535 65535
+123456: 100 200 300 400 500 600 700 800
+0: 65535 65535 65535 65535 65535 0",
+        )
+        .unwrap();
+        assert_eq!(part1(&equations), Ok(190));
+        assert_eq!(part2(&equations), Ok(190));
+        assert!(!equations[2].is_satisfiable_with_concat());
+        assert!(equations[3].is_satisfiable());
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        let equations = parse(
+            "190: 10 19
+18445618199572250625: 65535 65535 65535 65535
+18445618199572250625: 65535 65535 65535 65535",
+        )
+        .unwrap();
+        assert_eq!(part1(&equations).unwrap_err().line, Some(3));
+        assert_eq!(part2(&equations).unwrap_err().line, Some(3));
     }
 }
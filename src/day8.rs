@@ -1,63 +1,46 @@
 use std::{
     collections::{HashMap, HashSet},
-    convert::Infallible,
     str::FromStr,
 };
 
 use itertools::Itertools;
 
+use crate::{
+    error::{ParseError, SolveError},
+    grid::Grid,
+    solution::Solution,
+};
+
 // Yeah, the name isn't that great because we use it for both antenas and antinodes
 // pretty much this is just a 2d location
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Antenna {
-    row: u8,
-    column: u8,
-}
-
-fn get_num_with_inc_until_max(num: i16, max: i16, diff: i16) -> Vec<i16> {
-    let mut num = num;
-    let mut nums = vec![];
-    while num < max {
-        nums.push(num);
-        num += diff;
-    }
-
-    nums
-}
-
-fn get_num_with_dec_until_min(num: i16, min: i16, diff: i16) -> Vec<i16> {
-    let mut num = num;
-    let mut nums = vec![];
-    while num >= min {
-        nums.push(num);
-        num -= diff;
-    }
-
-    nums
+pub struct Antenna {
+    pub row: usize,
+    pub column: usize,
 }
 
 impl Antenna {
     fn get_antinodes(
         &self,
         other: &Self,
-        num_rows: u8,
-        num_columns: u8,
+        num_rows: usize,
+        num_columns: usize,
     ) -> (Option<Antenna>, Option<Antenna>) {
-        let (self_row, self_column) = (self.row as i16, self.column as i16);
-        let (other_row, other_column) = (other.row as i16, other.column as i16);
+        let (self_row, self_column) = (self.row as isize, self.column as isize);
+        let (other_row, other_column) = (other.row as isize, other.column as isize);
 
         // We now want to reduce the difference of rows from the antena that has a lower
         // row number, and add the diff to the greater row number
 
-        // should always fit in i16 because we are dealing with a grid of max u8 columns and rows
-        let row_diff = self_row.abs_diff(other_row) as i16;
+        // rows and columns are indices into a grid in memory, so they always fit in isize
+        let row_diff = self_row.abs_diff(other_row) as isize;
         let (self_row, other_row) = if self_row > other_row {
             (self_row + row_diff, other_row - row_diff)
         } else {
             (self_row - row_diff, other_row + row_diff)
         };
 
-        let column_diff = self_column.abs_diff(other_column) as i16;
+        let column_diff = self_column.abs_diff(other_column) as isize;
         let (self_column, other_column) = if self_column > other_column {
             (self_column + column_diff, other_column - column_diff)
         } else {
@@ -65,27 +48,27 @@ impl Antenna {
         };
 
         // now make sure we are in valid bounds
-        let a1 = if self_row < num_rows as i16
+        let a1 = if self_row < num_rows as isize
             && self_row >= 0
             && self_column >= 0
-            && self_column < num_columns as i16
+            && self_column < num_columns as isize
         {
             Some(Antenna {
-                row: self_row as u8,
-                column: self_column as u8,
+                row: self_row as usize,
+                column: self_column as usize,
             })
         } else {
             None
         };
 
-        let a2 = if other_row < num_rows as i16
+        let a2 = if other_row < num_rows as isize
             && other_row >= 0
             && other_column >= 0
-            && other_column < num_columns as i16
+            && other_column < num_columns as isize
         {
             Some(Antenna {
-                row: other_row as u8,
-                column: other_column as u8,
+                row: other_row as usize,
+                column: other_column as usize,
             })
         } else {
             None
@@ -97,57 +80,44 @@ impl Antenna {
     fn get_antinodes_any_distance(
         &self,
         other: &Self,
-        num_rows: u8,
-        num_columns: u8,
+        num_rows: usize,
+        num_columns: usize,
     ) -> Vec<Antenna> {
-        let (self_row, self_column) = (self.row as i16, self.column as i16);
-        let (other_row, other_column) = (other.row as i16, other.column as i16);
-
-        // should always fit in i16 because we are dealing with a grid of max u8 columns and rows
-        let row_diff = self_row.abs_diff(other_row) as i16;
-        let (self_rows, other_rows) = if self_row > other_row {
-            let self_rows = get_num_with_inc_until_max(self_row, num_rows as i16, row_diff);
-            let other_rows = get_num_with_dec_until_min(other_row, 0, row_diff);
-            (self_rows, other_rows)
-        } else {
-            let other_rows = get_num_with_inc_until_max(other_row, num_rows as i16, row_diff);
-            let self_rows = get_num_with_dec_until_min(self_row, 0, row_diff);
-            (self_rows, other_rows)
+        let (self_row, self_column) = (self.row as isize, self.column as isize);
+        let (other_row, other_column) = (other.row as isize, other.column as isize);
+        let in_bounds = |row: isize, column: isize| {
+            (0..num_rows as isize).contains(&row) && (0..num_columns as isize).contains(&column)
         };
 
-        let column_diff = self_column.abs_diff(other_column) as i16;
-        let (self_columns, other_columns) = if self_column > other_column {
-            let self_columns =
-                get_num_with_inc_until_max(self_column, num_columns as i16, column_diff);
-            let other_columns = get_num_with_dec_until_min(other_column, 0, column_diff);
-            (self_columns, other_columns)
-        } else {
-            let other_columns =
-                get_num_with_inc_until_max(other_column, num_columns as i16, column_diff);
-            let self_columns = get_num_with_dec_until_min(self_column, 0, column_diff);
-            (self_columns, other_columns)
-        };
+        // Walk from this antena towards the other one and past it, then away from it.
+        // The antenas are at different locations, so at least one of the steps isn't 0
+        let (row_diff, column_diff) = (other_row - self_row, other_column - self_column);
+        let mut antinodes = Vec::new();
+        for (row_step, column_step) in [(row_diff, column_diff), (-row_diff, -column_diff)] {
+            let (mut row, mut column) = (self_row, self_column);
+            while in_bounds(row, column) {
+                antinodes.push(Antenna {
+                    row: row as usize,
+                    column: column as usize,
+                });
+                row += row_step;
+                column += column_step;
+            }
+        }
 
-        self_rows
-            .into_iter()
-            .zip(self_columns)
-            .chain(other_rows.into_iter().zip(other_columns))
-            .map(|(row, column)| Antenna {
-                row: row as u8,
-                column: column as u8,
-            })
-            .collect()
+        antinodes
     }
 }
 
-struct AntennaGrid {
-    num_rows: u8,
-    num_columns: u8,
+#[derive(Debug)]
+pub struct AntennaGrid {
+    num_rows: usize,
+    num_columns: usize,
     antenas: HashMap<char, Vec<Antenna>>,
 }
 
 impl AntennaGrid {
-    fn find_antinodes(&self) -> HashSet<Antenna> {
+    pub fn find_antinodes(&self) -> HashSet<Antenna> {
         // Iterate over all antenas in pairs and find their absolute difference
         let mut locations = HashSet::new();
         for signal_antenas in self.antenas.values() {
@@ -166,12 +136,12 @@ impl AntennaGrid {
         locations
     }
 
-    fn find_antinodes_any_distance(&self) -> HashSet<Antenna> {
+    pub fn find_antinodes_any_distance(&self) -> HashSet<Antenna> {
         let mut locations = HashSet::new();
         for signal_antenas in self.antenas.values() {
             for (a1, a2) in signal_antenas.iter().tuple_combinations() {
                 let antenas = a1.get_antinodes_any_distance(a2, self.num_rows, self.num_columns);
-                locations.extend(antenas.into_iter());
+                locations.extend(antenas);
             }
         }
 
@@ -180,28 +150,29 @@ impl AntennaGrid {
 }
 
 impl FromStr for AntennaGrid {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut num_rows = 0;
-        let mut num_columns = 0;
+        let map = Grid::parse(s, |c| match c {
+            '.' => Ok(None),
+            c if c.is_whitespace() => Err("expected an antenna or `.`".to_string()),
+            c => Ok(Some(c)),
+        })?;
+
+        let (num_rows, num_columns) = (map.height(), map.width());
+
         let mut antenas = HashMap::new();
-        for row in s.lines() {
-            num_columns = 0;
-            for c in row.chars() {
-                if c != '.' {
-                    let antena = Antenna {
-                        row: num_rows,
-                        column: num_columns,
-                    };
-                    antenas
-                        .entry(c)
-                        .and_modify(|e: &mut Vec<Antenna>| e.push(antena))
-                        .or_insert_with(|| vec![antena]);
-                }
-                num_columns += 1;
+        for (point, frequency) in map.iter() {
+            if let Some(frequency) = frequency {
+                let antena = Antenna {
+                    row: point.row,
+                    column: point.column,
+                };
+                antenas
+                    .entry(*frequency)
+                    .and_modify(|e: &mut Vec<Antenna>| e.push(antena))
+                    .or_insert_with(|| vec![antena]);
             }
-            num_rows += 1;
         }
 
         Ok(Self {
@@ -212,43 +183,80 @@ impl FromStr for AntennaGrid {
     }
 }
 
-pub fn part1(input: &str) -> usize {
-    let grid: AntennaGrid = input.parse().unwrap();
+pub fn parse(input: &str) -> Result<AntennaGrid, ParseError> {
+    input.parse()
+}
+
+pub fn part1(grid: &AntennaGrid) -> usize {
     let antinodes = grid.find_antinodes();
     antinodes.len()
 }
 
-pub fn part2(input: &str) -> usize {
-    let grid: AntennaGrid = input.parse().unwrap();
+pub fn part2(grid: &AntennaGrid) -> usize {
     let antinodes = grid.find_antinodes_any_distance();
     antinodes.len()
 }
 
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = AntennaGrid;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError> {
+        Ok(part2(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = r#"............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............
-"#;
+    const INPUT: &str = include_str!("../inputs/day08.example.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 14);
+        assert_eq!(part1(&parse(INPUT).unwrap()), 14);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 34);
+        assert_eq!(part2(&parse(INPUT).unwrap()), 34);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("..a.\n.a.\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+    }
+
+    #[test]
+    fn test_large_grid() {
+        // antennas past row and column 255, with antinodes on both sides of them
+        let mut rows = vec![".".repeat(1000); 1000];
+        rows[300].replace_range(300..301, "a");
+        rows[400].replace_range(500..501, "a");
+        let grid = parse(&rows.join("\n")).unwrap();
+        assert_eq!(part1(&grid), 2);
+        assert_eq!(part2(&grid), 5);
+    }
+
+    #[test]
+    fn test_antennas_in_line() {
+        // found by the day 8 input generator, part 2 used to loop forever
+        let grid = parse("a.a...\n......\na.....").unwrap();
+        assert_eq!(part1(&grid), 1);
+        assert_eq!(part2(&grid), 4);
     }
 }
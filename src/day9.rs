@@ -1,37 +1,44 @@
 use itertools::Itertools;
 
+use crate::{
+    checked,
+    error::{ParseError, SolveError},
+    solution::Solution,
+};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum DiskEntry {
+pub enum DiskEntry {
     Free,
     File { id: u16 },
 }
 
-struct MemoryMap {
+pub struct MemoryMap {
     entries: Vec<DiskEntry>,
 }
 
 impl MemoryMap {
-    fn from_packed(packed: &str) -> Self {
+    /// Expand the output of [`parse`], panics on more files than fit in a `u16` id
+    pub fn from_packed(packed: &[u8]) -> Self {
         let mut id_number = 0;
         let mut entries = Vec::new();
-        packed
-            .chars()
-            .enumerate()
-            .into_iter()
-            .for_each(|(i, num_blocks)| {
-                let num_blocks = (num_blocks as u8 - b'0') as usize;
-                let this_entries = if i % 2 == 0 {
-                    let entries = vec![DiskEntry::File { id: id_number }; num_blocks];
-                    id_number += 1;
-                    entries
-                } else {
-                    vec![DiskEntry::Free; num_blocks]
-                };
-                entries.extend(this_entries);
-            });
+        packed.iter().enumerate().for_each(|(i, num_blocks)| {
+            let num_blocks = *num_blocks as usize;
+            let this_entries = if i % 2 == 0 {
+                let entries = vec![DiskEntry::File { id: id_number }; num_blocks];
+                id_number += 1;
+                entries
+            } else {
+                vec![DiskEntry::Free; num_blocks]
+            };
+            entries.extend(this_entries);
+        });
 
         Self { entries }
     }
+
+    pub fn entries(&self) -> &[DiskEntry] {
+        &self.entries
+    }
 }
 
 impl std::fmt::Display for MemoryMap {
@@ -47,13 +54,48 @@ impl std::fmt::Display for MemoryMap {
     }
 }
 
+/// Parse the dense disk map into the number of blocks of each file and free space
+pub fn parse(input: &str) -> Result<Vec<u8>, ParseError> {
+    let packed: Vec<u8> = input
+        .trim_end()
+        .chars()
+        .enumerate()
+        .map(|(column, num_blocks)| match num_blocks.to_digit(10) {
+            // file ids are stored as u16, every other digit is a new file
+            Some(_) if column / 2 > u16::MAX as usize => Err(ParseError::new(
+                1,
+                column + 1,
+                format!(
+                    "the disk map can have at most {} files",
+                    u16::MAX as usize + 1
+                ),
+            )),
+            Some(num_blocks) => Ok(num_blocks as u8),
+            None => Err(ParseError::new(
+                1,
+                column + 1,
+                format!("expected a digit, found `{num_blocks}`"),
+            )),
+        })
+        .collect::<Result<_, _>>()?;
+
+    // compacting needs at least one block to move
+    if packed.iter().step_by(2).all(|num_blocks| *num_blocks == 0) {
+        return Err(ParseError::new(1, 1, "the disk map has no file blocks"));
+    }
+
+    Ok(packed)
+}
+
 // Super not proud of my part1 solution lol
-pub fn part1(input: &str) -> usize {
-    let memory_map = MemoryMap::from_packed(input);
+pub fn part1(packed: &[u8]) -> Result<usize, SolveError> {
+    let memory_map = MemoryMap::from_packed(packed);
     let mut new_entries = Vec::new();
 
     let mut back_it = memory_map.entries.iter().enumerate().rev().fuse();
-    let (mut back_i, mut n) = back_it.next().unwrap();
+    let Some((mut back_i, mut n)) = back_it.next() else {
+        return Ok(0);
+    };
 
     for (i, entry) in memory_map.entries.iter().enumerate() {
         if i > back_i {
@@ -66,69 +108,77 @@ pub fn part1(input: &str) -> usize {
                 let new_id = loop {
                     match n {
                         DiskEntry::Free => (),
-                        DiskEntry::File { id } => break *id,
+                        DiskEntry::File { id } => break Some(*id),
+                    }
+                    match back_it.next() {
+                        Some(next) => (back_i, n) = next,
+                        None => break None,
                     }
-                    (back_i, n) = back_it.next().unwrap();
                 };
 
-                // now check if we aren't overlapping
-                if i > back_i {
+                // now check if we aren't overlapping, or ran out of files to move
+                let Some(new_id) = new_id.filter(|_| i <= back_i) else {
                     break;
-                }
+                };
                 new_entries.push(new_id);
-                (back_i, n) = back_it.next().unwrap();
+                match back_it.next() {
+                    Some(next) => (back_i, n) = next,
+                    None => break,
+                }
             }
             DiskEntry::File { id } => new_entries.push(*id),
         }
     }
 
-    eprintln!("{}", new_entries.len());
+    log::debug!("{} blocks after compacting", new_entries.len());
 
+    // the whole disk map is on the first line
     new_entries
         .into_iter()
         .enumerate()
-        .map(|(i, n)| i * n as usize)
-        .sum()
+        .try_fold(0, |checksum, (i, n)| {
+            checked::add(checksum, checked::mul(i, n as usize)?)
+        })
+        .map_err(|e| e.on_line(1))
 }
 
 #[derive(Debug, Copy, Clone)]
-struct BlocksAndIndex {
-    num_blocks: u8,
-    index: usize,
-    id: u16,
+pub struct BlocksAndIndex {
+    pub num_blocks: u8,
+    pub index: usize,
+    pub id: u16,
 }
 
-fn display_blocks(blocks: &[BlocksAndIndex]) {
-    let mut blocks: Vec<_> = blocks.iter().copied().collect();
+/// Draw the disk like the puzzle does, with `.` for the free blocks
+fn display_blocks(blocks: &[BlocksAndIndex]) -> String {
+    let mut blocks: Vec<_> = blocks.to_vec();
     blocks.sort_by_key(|x| x.index);
+    let mut disk = String::new();
     for (i, b) in blocks.iter().enumerate() {
         for _ in 0..b.num_blocks {
-            print!("{}", b.id)
+            disk.push_str(&b.id.to_string());
         }
 
         if let Some(next) = blocks.get(i + 1) {
             let diff = next.index - (b.index + b.num_blocks as usize);
-            for _ in 0..diff {
-                print!(".");
-            }
+            disk.push_str(&".".repeat(diff));
         }
     }
-    println!();
+    disk
 }
 
-struct MemoryMapOptimized {
+pub struct MemoryMapOptimized {
     files: Vec<BlocksAndIndex>,
     free: Vec<BlocksAndIndex>,
 }
 
 impl MemoryMapOptimized {
-    fn from_packed(packed: &str) -> Self {
+    /// Positions of the output of [`parse`], panics on more files than fit in a `u16` id
+    pub fn from_packed(packed: &[u8]) -> Self {
         let mut files = Vec::with_capacity(packed.len() / 2);
         let mut free = Vec::with_capacity(packed.len() / 2);
         let mut spaced_used = 0;
-        for (i, num_blocks) in packed.char_indices() {
-            let num_blocks = num_blocks as u8 - b'0';
-
+        for (i, num_blocks) in packed.iter().copied().enumerate() {
             if i % 2 == 0 {
                 files.push(BlocksAndIndex {
                     num_blocks,
@@ -148,7 +198,7 @@ impl MemoryMapOptimized {
         Self { files, free }
     }
 
-    fn move_files(mut self) -> Vec<BlocksAndIndex> {
+    pub fn move_files(mut self) -> Vec<BlocksAndIndex> {
         // The free is sorted by index which is what we want. Find the leftmost block
         // that is large enough to fit.
         let mut free_blocks: Vec<_> = self.free;
@@ -188,35 +238,89 @@ impl MemoryMapOptimized {
     }
 }
 
-pub fn part2(input: &str) -> usize {
-    let memory_map = MemoryMapOptimized::from_packed(input);
-    // display_blocks(&memory_map.files);
+pub fn part2(packed: &[u8]) -> Result<usize, SolveError> {
+    let memory_map = MemoryMapOptimized::from_packed(packed);
+    // the arguments are only evaluated when trace logging is enabled
+    log::trace!("before: {}", display_blocks(&memory_map.files));
     let files_list = memory_map.move_files();
-    // display_blocks(&files_list);
+    log::trace!("after: {}", display_blocks(&files_list));
+    // the whole disk map is on the first line
     files_list
         .into_iter()
-        .map(|entry| {
+        .flat_map(|entry| {
             (entry.index..entry.index + entry.num_blocks as usize)
-                .into_iter()
-                .map(|index| index * entry.id as usize)
-                .sum::<usize>()
+                .map(move |index| (index, entry.id))
         })
-        .sum()
+        .try_fold(0, |checksum, (index, id)| {
+            checked::add(checksum, checked::mul(index, id as usize)?)
+        })
+        .map_err(|e| e.on_line(1))
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<u8>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError> {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = r#"2333133121414131402"#;
+    const INPUT: &str = include_str!("../inputs/day09.example.txt");
 
-    // #[test]
-    // fn test_part1() {
-    //     assert_eq!(part1(INPUT), 1928);
-    // }
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse(INPUT).unwrap()), Ok(1928));
+    }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 2858);
+        assert_eq!(part2(&parse(INPUT).unwrap()), Ok(2858));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("2333x33").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+
+        let error = parse("2333\n33").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+
+        let error = parse(&"1".repeat(140_000)).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 131_073));
+
+        let error = parse("").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+
+        let error = parse("0\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+
+        let error = parse("09").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+    }
+
+    #[test]
+    fn test_part1_leading_free_space() {
+        let packed = parse("060000000000000000000000000000000449").unwrap();
+        // only file 17 has blocks, it moves to the start of the disk
+        assert_eq!(part1(&packed), Ok(17 * (1 + 2 + 3)));
+        assert_eq!(part1(&[]), Ok(0));
     }
 }
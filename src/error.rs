@@ -0,0 +1,180 @@
+//! Errors shared by all the input parsers and solutions
+use std::{fmt, str::FromStr};
+
+/// Invalid puzzle input, with the 1-based line and column of where it went wrong
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub reason: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, reason: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            reason: reason.into(),
+        }
+    }
+
+    /// Error pointing at `part`, which must be a substring of `line`
+    pub fn at(line_number: usize, line: &str, part: &str, reason: impl Into<String>) -> Self {
+        Self::new(line_number, column_of(line, part), reason)
+    }
+
+    /// Error pointing right after the last character of the input
+    pub fn end_of_input(input: &str, reason: impl Into<String>) -> Self {
+        let (line, last) = lines(input).last().unwrap_or((1, ""));
+        Self::new(line, last.chars().count() + 1, reason)
+    }
+
+    /// Move an error from a parser that only saw a single line to the right line
+    ///
+    /// Parsers of a single line always report line 1, so the caller that knows
+    /// where the line came from fixes it up.
+    pub fn on_line(self, line: usize) -> Self {
+        Self { line, ..self }
+    }
+
+    /// The offending line of the input with a marker under the column
+    pub fn snippet(&self, input: &str) -> Option<String> {
+        let (_, line) = lines(input).nth(self.line.checked_sub(1)?)?;
+        let number = self.line.to_string();
+        let padding = " ".repeat(number.len());
+        let marker = " ".repeat(self.column.saturating_sub(1));
+        Some(format!(
+            "{padding} |\n{number} | {line}\n{padding} | {marker}^"
+        ))
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.reason
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A valid input that can't be solved, like an answer that doesn't fit in its type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveError {
+    /// 1-based line of the input that caused it, when there is a single one
+    pub line: Option<usize>,
+    pub reason: String,
+}
+
+impl SolveError {
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            line: None,
+            reason: reason.into(),
+        }
+    }
+
+    /// Blame the line of the input the failed computation came from
+    pub fn on_line(self, line: usize) -> Self {
+        Self {
+            line: Some(line),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.reason),
+            None => write!(f, "{}", self.reason),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// 1-based column of `part` inside of `line`, counted in characters
+///
+/// `part` must be a slice of `line`, which is what we get from `split` and friends.
+pub fn column_of(line: &str, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize)
+        .checked_sub(line.as_ptr() as usize)
+        .filter(|offset| *offset <= line.len())
+        .expect("part must be a slice of line");
+    line[..offset].chars().count() + 1
+}
+
+/// Lines of the input with their 1-based number
+///
+/// Trailing whitespace, including the `\r` of CRLF line endings, is removed
+/// so pasted inputs parse the same as downloaded ones.
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim_end()))
+}
+
+/// Parse a number that is a part of `line`, pointing at it when it's invalid
+pub fn parse_number<T>(line_number: usize, line: &str, part: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    part.parse().map_err(|e| {
+        ParseError::at(
+            line_number,
+            line,
+            part,
+            format!("invalid number `{part}`: {e}"),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_of() {
+        let line = "12 | 34";
+        let part = line.split(" | ").nth(1).unwrap();
+        assert_eq!(column_of(line, part), 6);
+        assert_eq!(column_of(line, &line[7..]), 8);
+    }
+
+    #[test]
+    fn test_lines_trims_crlf_and_whitespace() {
+        let lines: Vec<_> = lines("1 2  \r\n3 4\r\n").collect();
+        assert_eq!(lines, vec![(1, "1 2"), (2, "3 4")]);
+    }
+
+    #[test]
+    fn test_parse_number() {
+        let line = "7: 1x";
+        let part = &line[3..];
+        let error = parse_number::<u8>(2, line, part).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert!(error.reason.starts_with("invalid number `1x`"));
+    }
+
+    #[test]
+    fn test_snippet() {
+        let error = ParseError::new(2, 3, "bad");
+        assert_eq!(
+            error.snippet("abc\ndef\n").unwrap(),
+            "  |\n2 | def\n  |   ^"
+        );
+        assert_eq!(ParseError::new(5, 1, "bad").snippet("abc"), None);
+    }
+
+    #[test]
+    fn test_end_of_input() {
+        let error = ParseError::end_of_input("ab\ncd\n", "missing");
+        assert_eq!((error.line, error.column), (2, 3));
+    }
+}
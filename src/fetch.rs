@@ -0,0 +1,211 @@
+//! Download puzzle inputs into the input store, at most once per day
+//!
+//! Inputs are personal, so the site needs the `session` cookie of a logged in
+//! browser. Once an input is in the store it is never downloaded again, the
+//! puzzle inputs don't change and the site asks to not be hammered.
+//!
+//! Behind the `fetch` feature, so the library doesn't pull in an HTTP client by default.
+use std::{fmt, fs, io, path::PathBuf};
+
+use crate::inputs::{InputKind, InputStore};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const USER_AGENT: &str = concat!("aoc-rs-2024/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug)]
+pub enum FetchError {
+    InvalidDay(u8),
+    /// The server answered with an error status
+    Status {
+        url: String,
+        status: u16,
+    },
+    /// The server couldn't be reached, or the response couldn't be read
+    Http {
+        url: String,
+        reason: String,
+    },
+    Empty(String),
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::InvalidDay(day) => write!(f, "day {day} is not between 1 and 25"),
+            FetchError::Status { url, status } => {
+                write!(f, "{url} answered with status {status}")?;
+                match status {
+                    400 => write!(f, ", the session token is probably invalid or expired"),
+                    404 => write!(f, ", the puzzle is probably not unlocked yet"),
+                    _ => Ok(()),
+                }
+            }
+            FetchError::Http { url, reason } => write!(f, "can't download {url}: {reason}"),
+            FetchError::Empty(url) => write!(f, "{url} returned an empty input"),
+            FetchError::Io(path, e) => write!(f, "can't write {}: {e}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// Where the input of a fetch came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fetched {
+    Downloaded,
+    Cached,
+}
+
+/// Downloads real inputs from the puzzle site, or a stand-in of it
+#[derive(Debug, Clone)]
+pub struct Fetcher {
+    base_url: String,
+    session: String,
+}
+
+impl Fetcher {
+    pub fn new(base_url: impl Into<String>, session: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            session: session.into(),
+        }
+    }
+
+    pub fn url(&self, day: u8) -> String {
+        format!("{}/2024/day/{day}/input", self.base_url)
+    }
+
+    /// Download the input of a day into the store, unless it's already there
+    pub fn fetch(&self, store: &InputStore, day: u8) -> Result<(PathBuf, Fetched), FetchError> {
+        if !(1..=25).contains(&day) {
+            return Err(FetchError::InvalidDay(day));
+        }
+
+        let path = store.path(day, InputKind::Real);
+        if fs::read_to_string(&path).is_ok_and(|input| !input.trim().is_empty()) {
+            return Ok((path, Fetched::Cached));
+        }
+
+        let url = self.url(day);
+        log::debug!("downloading {url}");
+        let http_error = |e: ureq::Error| match e {
+            ureq::Error::StatusCode(status) => FetchError::Status {
+                url: url.clone(),
+                status,
+            },
+            e => FetchError::Http {
+                url: url.clone(),
+                reason: e.to_string(),
+            },
+        };
+        let input = ureq::get(&url)
+            .header("Cookie", format!("session={}", self.session))
+            .header("User-Agent", USER_AGENT)
+            .call()
+            .map_err(http_error)?
+            .body_mut()
+            .read_to_string()
+            .map_err(http_error)?;
+        if input.trim().is_empty() {
+            return Err(FetchError::Empty(url));
+        }
+
+        // write next to it first, so an interrupted download isn't mistaken for the input
+        let io_error = |e| FetchError::Io(path.clone(), e);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let partial = path.with_extension("txt.part");
+        fs::write(&partial, input).map_err(io_error)?;
+        fs::rename(&partial, &path).map_err(io_error)?;
+
+        Ok((path, Fetched::Downloaded))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    use super::*;
+
+    /// Answer every request with the given status and body, sending the request lines back
+    fn serve(status: u16, body: &'static str) -> (String, mpsc::Receiver<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (requests, received) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let request: Vec<String> = BufReader::new(&stream)
+                    .lines()
+                    .map(Result::unwrap)
+                    .take_while(|line| !line.is_empty())
+                    .collect();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Whatever\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+                if requests.send(request).is_err() {
+                    return;
+                }
+            }
+        });
+        (base_url, received)
+    }
+
+    fn temp_store(name: &str) -> (PathBuf, InputStore) {
+        let dir = std::env::temp_dir().join(format!("aoc-fetch-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        (dir.clone(), InputStore::new(dir))
+    }
+
+    #[test]
+    fn test_fetch_once() {
+        let (base_url, requests) = serve(200, "3   4\n4   3\n");
+        let (dir, store) = temp_store("once");
+        let fetcher = Fetcher::new(base_url + "/", "secret");
+
+        let (path, fetched) = fetcher.fetch(&store, 1).unwrap();
+        assert_eq!(fetched, Fetched::Downloaded);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "3   4\n4   3\n");
+        let request = requests.recv().unwrap();
+        assert_eq!(request[0], "GET /2024/day/1/input HTTP/1.1");
+        assert!(request
+            .iter()
+            .any(|line| line.eq_ignore_ascii_case("cookie: session=secret")));
+
+        assert_eq!(fetcher.fetch(&store, 1).unwrap(), (path, Fetched::Cached));
+        assert!(requests.try_recv().is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        let (base_url, _requests) = serve(400, "Puzzle inputs differ by user.");
+        let (dir, store) = temp_store("errors");
+        let fetcher = Fetcher::new(base_url, "expired");
+
+        assert!(matches!(
+            fetcher.fetch(&store, 2),
+            Err(FetchError::Status { status: 400, .. })
+        ));
+        assert!(!store.path(2, InputKind::Real).exists());
+        assert!(matches!(
+            fetcher.fetch(&store, 26),
+            Err(FetchError::InvalidDay(26))
+        ));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
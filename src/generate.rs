@@ -0,0 +1,327 @@
+//! Random puzzle inputs of any size, in the same shape as the real ones
+//!
+//! Real inputs can't be shared, and the examples are too small to benchmark or
+//! stress test with. `generate --day 6 --size 500 --seed 7` makes a 500x500 lab
+//! instead, and the same seed always gives the same input.
+use std::{collections::HashMap, fmt};
+
+use rand::{rngs::StdRng, seq::SliceRandom, RngExt, SeedableRng};
+
+use crate::day6;
+
+/// File ids of day 9 are stored as `u16`
+const DAY9_MAX_FILES: usize = u16::MAX as usize + 1;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GenerateError {
+    UnknownDay(u8),
+    /// The input format of the day can't hold this many lines, cells or files
+    InvalidSize {
+        day: u8,
+        size: usize,
+        max: usize,
+    },
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerateError::UnknownDay(day) => write!(f, "day {day} has no input generator"),
+            GenerateError::InvalidSize { day, size, max } => write!(
+                f,
+                "day {day} can't have an input of size {size}, it must be between 1 and {max}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+/// Generate a valid input for a day
+///
+/// The size is the number of lines for the days with an item per line, the
+/// width and height for the grids, and the number of files or stones otherwise.
+pub fn generate(day: u8, size: usize, seed: u64) -> Result<String, GenerateError> {
+    let (generator, max): (fn(&mut StdRng, usize) -> String, usize) = match day {
+        1 => (day1, usize::MAX),
+        2 => (day2, usize::MAX),
+        3 => (day3, usize::MAX),
+        4 => (day4, usize::MAX),
+        5 => (day5, usize::MAX),
+        6 => (day6, usize::MAX),
+        7 => (day7, usize::MAX),
+        8 => (day8, usize::MAX),
+        9 => (day9, DAY9_MAX_FILES),
+        10 => (day10, usize::MAX),
+        11 => (day11, usize::MAX),
+        _ => return Err(GenerateError::UnknownDay(day)),
+    };
+    if !(1..=max).contains(&size) {
+        return Err(GenerateError::InvalidSize { day, size, max });
+    }
+
+    Ok(generator(&mut StdRng::seed_from_u64(seed), size))
+}
+
+fn lines(lines: impl Iterator<Item = String>) -> String {
+    lines.map(|line| line + "\n").collect()
+}
+
+fn grid(
+    rng: &mut StdRng,
+    size: usize,
+    mut cell: impl FnMut(&mut StdRng, usize, usize) -> char,
+) -> String {
+    lines((0..size).map(|row| (0..size).map(|column| cell(rng, row, column)).collect()))
+}
+
+/// Two lists of location ids, a third of the right ones also show up on the left
+fn day1(rng: &mut StdRng, size: usize) -> String {
+    let mut left = Vec::with_capacity(size);
+    lines((0..size).map(|_| {
+        left.push(rng.random_range(10_000..100_000u32));
+        let right = if rng.random_bool(1.0 / 3.0) {
+            left[rng.random_range(0..left.len())]
+        } else {
+            rng.random_range(10_000..100_000)
+        };
+        format!("{}   {right}", left[left.len() - 1])
+    }))
+}
+
+/// Reports that mostly change by 1 to 3 levels in one direction, with some bad steps
+fn day2(rng: &mut StdRng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        let mut level: u8 = rng.random_range(1..=90);
+        let increasing = rng.random_bool(0.5);
+        let mut levels = vec![level.to_string()];
+        for _ in 1..rng.random_range(5..=8) {
+            let step = if rng.random_bool(0.1) {
+                rng.random_range(0..=6)
+            } else {
+                rng.random_range(1..=3)
+            };
+            let increasing = increasing != rng.random_bool(0.05);
+            level = if increasing {
+                level.saturating_add(step)
+            } else {
+                level.saturating_sub(step)
+            };
+            levels.push(level.to_string());
+        }
+        levels.join(" ")
+    }))
+}
+
+/// Corrupted memory, the size is the number of instructions and almost instructions
+fn day3(rng: &mut StdRng, size: usize) -> String {
+    const NOISE: &[u8] = b"!@#$%^&*()[]{}<>,;:'?/+-~ mulwhydontselectfromwhere";
+    let mut memory = String::new();
+    for i in 0..size {
+        let (a, b) = (rng.random_range(1..1000), rng.random_range(1..1000));
+        let instruction = match rng.random_range(0..10) {
+            0..6 => format!("mul({a},{b})"),
+            6 => "do()".to_string(),
+            7 => "don't()".to_string(),
+            8 => format!("mul({a},{b}]"),
+            _ => format!("mul ( {a},{b})"),
+        };
+        memory.push_str(&instruction);
+        for _ in 0..rng.random_range(0..8) {
+            memory.push(NOISE[rng.random_range(0..NOISE.len())] as char);
+        }
+        if i % 100 == 99 {
+            memory.push('\n');
+        }
+    }
+    memory.push('\n');
+    memory
+}
+
+/// A square of the letters of `XMAS`
+fn day4(rng: &mut StdRng, size: usize) -> String {
+    grid(rng, size, |rng, _, _| {
+        b"XMAS"[rng.random_range(0..4)] as char
+    })
+}
+
+/// Rules between every pair of 49 pages, so every update has a single right order
+fn day5(rng: &mut StdRng, size: usize) -> String {
+    let mut pages: Vec<u64> = (10..100).collect();
+    pages.shuffle(rng);
+    pages.truncate(49);
+
+    // the shuffled pages are the right order, rules can't have cycles
+    let mut rules = Vec::new();
+    for (i, before) in pages.iter().enumerate() {
+        for after in &pages[i + 1..] {
+            rules.push(format!("{before}|{after}"));
+        }
+    }
+    rules.shuffle(rng);
+
+    let mut indices: Vec<usize> = (0..pages.len()).collect();
+    let updates = (0..size).map(|_| {
+        indices.shuffle(rng);
+        let update = &mut indices[..rng.random_range(2..=11) * 2 + 1];
+        if rng.random_bool(0.5) {
+            update.sort_unstable();
+        }
+        update
+            .iter()
+            .map(|i| pages[*i].to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+    lines(rules.into_iter().chain([String::new()]).chain(updates))
+}
+
+/// A lab with a few obstructions that the guard eventually leaves, like the real ones
+///
+/// Guards on random maps tend to leave quickly, so the map with the longest walk of a
+/// few tries is picked.
+fn day6(rng: &mut StdRng, size: usize) -> String {
+    let mut longest: Option<(usize, String)> = None;
+    let mut tries = 0;
+    while tries < 10 || longest.is_none() {
+        tries += 1;
+        let guard = (rng.random_range(0..size), rng.random_range(0..size));
+        let map = grid(rng, size, |rng, row, column| {
+            if (row, column) == guard {
+                '^'
+            } else if rng.random_bool(0.05) {
+                '#'
+            } else {
+                '.'
+            }
+        });
+        let lab = day6::parse(&map).expect("generated labs are valid");
+        if let Ok(steps) = day6::walk(&lab).map(|walk| walk.len()) {
+            if longest.as_ref().is_none_or(|(longest, _)| steps > *longest) {
+                longest = Some((steps, map));
+            }
+        }
+    }
+    longest.expect("the loop only ends with a map").1
+}
+
+/// Equations of 2 to 8 numbers, half of them made true with random operators
+///
+/// Numbers are below 100 so even concatenating all of them fits in `u64`.
+fn day7(rng: &mut StdRng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        let numbers: Vec<u64> = (0..rng.random_range(2..=8))
+            .map(|_| rng.random_range(1..100))
+            .collect();
+        let mut result = numbers[0];
+        for number in &numbers[1..] {
+            result = match rng.random_range(0..3) {
+                0 => result + number,
+                1 => result * number,
+                _ => result * 10u64.pow(number.ilog10() + 1) + number,
+            };
+        }
+        if rng.random_bool(0.5) {
+            result += rng.random_range(1..10);
+        }
+        let numbers: Vec<_> = numbers.iter().map(u64::to_string).collect();
+        format!("{result}: {}", numbers.join(" "))
+    }))
+}
+
+/// A square with 2 to 4 antennas for each of up to 62 frequencies
+fn day8(rng: &mut StdRng, size: usize) -> String {
+    const FREQUENCIES: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut cells: Vec<(usize, usize)> = (0..size)
+        .flat_map(|row| (0..size).map(move |column| (row, column)))
+        .collect();
+    cells.shuffle(rng);
+
+    let mut cells = cells.into_iter();
+    let mut antennas = HashMap::new();
+    for frequency in &FREQUENCIES[..(size / 3).clamp(1, FREQUENCIES.len())] {
+        for cell in cells.by_ref().take(rng.random_range(2..=4)) {
+            antennas.insert(cell, *frequency as char);
+        }
+    }
+    grid(rng, size, |_, row, column| {
+        antennas.get(&(row, column)).copied().unwrap_or('.')
+    })
+}
+
+/// A disk map of files with at least one block, and free space of any size between them
+fn day9(rng: &mut StdRng, size: usize) -> String {
+    let mut disk_map = String::with_capacity(size * 2);
+    for file in 0..size {
+        disk_map.push(char::from(b'0' + rng.random_range(1..=9)));
+        if file + 1 < size {
+            disk_map.push(char::from(b'0' + rng.random_range(0..=9)));
+        }
+    }
+    disk_map.push('\n');
+    disk_map
+}
+
+/// Diagonal slopes from 0 to 9 with some noise, so there are plenty of trails
+fn day10(rng: &mut StdRng, size: usize) -> String {
+    grid(rng, size, |rng, row, column| {
+        let height = if rng.random_bool(0.3) {
+            rng.random_range(0..10)
+        } else {
+            (row + column) % 10
+        };
+        char::from(b'0' + height as u8)
+    })
+}
+
+/// A single line of stones with up to 6 digits
+fn day11(rng: &mut StdRng, size: usize) -> String {
+    let stones: Vec<_> = (0..size)
+        .map(|_| rng.random_range(0..1_000_000u64).to_string())
+        .collect();
+    stones.join(" ") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, SOLUTIONS};
+
+    #[test]
+    fn test_generated_inputs_solve() {
+        for solution in SOLUTIONS {
+            let day = solution.day();
+            for (size, seed) in [(1, 0), (2, 1), (12, 2)] {
+                let input = match generate(day, size, seed) {
+                    Ok(input) => input,
+                    // days that were just scaffolded don't have a generator yet
+                    Err(GenerateError::UnknownDay(_)) => continue,
+                    Err(e) => panic!("day {day} size {size}: {e}"),
+                };
+                let parsed = solution
+                    .parse(&input)
+                    .unwrap_or_else(|e| panic!("day {day} size {size}: {e}\n{input}"));
+                for part in Part::ALL {
+                    assert!(parsed.solve(part).is_ok(), "day {day} size {size}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_seed() {
+        assert_eq!(generate(6, 20, 7), generate(6, 20, 7));
+        assert_ne!(generate(6, 20, 7), generate(6, 20, 8));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(generate(26, 10, 0), Err(GenerateError::UnknownDay(26)));
+        assert!(matches!(
+            generate(1, 0, 0),
+            Err(GenerateError::InvalidSize { size: 0, .. })
+        ));
+        assert!(generate(9, DAY9_MAX_FILES, 0).is_ok());
+        assert!(generate(9, DAY9_MAX_FILES + 1, 0).is_err());
+    }
+}
@@ -0,0 +1,340 @@
+//! A 2d grid of cells, shared by all the puzzles with a map as their input
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::error::{self, ParseError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub row: usize,
+    pub column: usize,
+}
+
+impl Point {
+    pub const fn new(row: usize, column: usize) -> Self {
+        Self { row, column }
+    }
+}
+
+/// The 8 directions from a cell, clockwise starting from up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// Directions that share an edge with the cell
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Directions that only share a corner with the cell
+    pub const DIAGONAL: [Direction; 4] = [
+        Direction::UpRight,
+        Direction::DownRight,
+        Direction::DownLeft,
+        Direction::UpLeft,
+    ];
+
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    /// Change in (row, column) when taking a step in this direction
+    pub const fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::UpRight => (-1, 1),
+            Direction::Right => (0, 1),
+            Direction::DownRight => (1, 1),
+            Direction::Down => (1, 0),
+            Direction::DownLeft => (1, -1),
+            Direction::Left => (0, -1),
+            Direction::UpLeft => (-1, -1),
+        }
+    }
+
+    /// Index of the direction in [`Direction::ALL`], handy for lookup tables
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub const fn turn_right(self) -> Self {
+        Self::ALL[(self.index() + 2) % 8]
+    }
+
+    pub const fn turn_left(self) -> Self {
+        Self::ALL[(self.index() + 6) % 8]
+    }
+
+    pub const fn opposite(self) -> Self {
+        Self::ALL[(self.index() + 4) % 8]
+    }
+}
+
+/// Grid of `T` stored row by row in a single vector
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parse a grid with a character per cell, every line is a row
+    ///
+    /// All rows must have the same length. `cell` converts each character, and
+    /// returns the reason it's invalid otherwise.
+    pub fn parse<F>(input: &str, mut cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Result<T, String>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::with_capacity(input.len());
+        for (line_number, line) in error::lines(input.trim_end()) {
+            let mut row_width = 0;
+            for (column, c) in line.chars().enumerate() {
+                let item =
+                    cell(c).map_err(|reason| ParseError::new(line_number, column + 1, reason))?;
+                cells.push(item);
+                row_width += 1;
+            }
+
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    return Err(ParseError::new(
+                        line_number,
+                        row_width.min(width) + 1,
+                        format!("expected {width} cells like the first line"),
+                    ));
+                }
+                Some(_) => (),
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.row < self.height && point.column < self.width
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        if self.contains(point) {
+            self.cells.get(point.row * self.width + point.column)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        if self.contains(point) {
+            self.cells.get_mut(point.row * self.width + point.column)
+        } else {
+            None
+        }
+    }
+
+    /// The point one step away in `direction`, if it's still inside of the grid
+    pub fn step(&self, point: Point, direction: Direction) -> Option<Point> {
+        let (row_delta, column_delta) = direction.delta();
+        let next = Point {
+            row: point.row.checked_add_signed(row_delta)?,
+            column: point.column.checked_add_signed(column_delta)?,
+        };
+        self.contains(next).then_some(next)
+    }
+
+    /// Neighbors sharing an edge with `point`
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ORTHOGONAL
+            .into_iter()
+            .filter_map(move |direction| self.step(point, direction))
+    }
+
+    /// Neighbors sharing an edge or a corner with `point`
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(point, direction))
+    }
+
+    /// All points of the grid, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |column| Point { row, column }))
+    }
+
+    /// All cells with their point, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on 0, but then there are no cells anyways
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// First point, row by row, where the cell matches
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(point, _)| point)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point:?} is outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point:?} is outside of the grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Result<Grid<u8>, ParseError> {
+        Grid::parse(input, |c| {
+            c.to_digit(10)
+                .map(|digit| digit as u8)
+                .ok_or_else(|| format!("`{c}` is not a digit"))
+        })
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid = digits("123\r\n456\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(1, 0)], 4);
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 3)), None);
+        assert_eq!(grid.to_string(), "123\n456\n");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = digits("123\n4x6").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+
+        let error = digits("123\n45").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let grid = digits("").unwrap();
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.points().count(), 0);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = digits("123\n456\n789").unwrap();
+        let values = |points: Vec<Point>| points.into_iter().map(|p| grid[p]).collect::<Vec<_>>();
+        assert_eq!(
+            values(grid.neighbors4(Point::new(0, 0)).collect()),
+            vec![2, 4]
+        );
+        assert_eq!(
+            values(grid.neighbors4(Point::new(1, 1)).collect()),
+            vec![2, 6, 8, 4]
+        );
+        assert_eq!(
+            values(grid.neighbors8(Point::new(1, 1)).collect()),
+            vec![2, 3, 6, 9, 8, 7, 4, 1]
+        );
+        assert_eq!(
+            values(grid.neighbors8(Point::new(2, 2)).collect()),
+            vec![6, 8, 5]
+        );
+    }
+
+    #[test]
+    fn test_step_and_turn() {
+        let grid = Grid::new(2, 3, 0);
+        assert_eq!(grid.step(Point::new(0, 0), Direction::Up), None);
+        assert_eq!(
+            grid.step(Point::new(0, 0), Direction::DownRight),
+            Some(Point::new(1, 1))
+        );
+        assert_eq!(grid.step(Point::new(0, 1), Direction::Right), None);
+
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::UpLeft.opposite(), Direction::DownRight);
+    }
+
+    #[test]
+    fn test_position() {
+        let mut grid = digits("123\n456").unwrap();
+        assert_eq!(grid.position(|cell| *cell == 5), Some(Point::new(1, 1)));
+        grid[Point::new(0, 2)] = 5;
+        assert_eq!(grid.position(|cell| *cell == 5), Some(Point::new(0, 2)));
+        assert_eq!(grid.position(|cell| *cell == 7), None);
+    }
+}
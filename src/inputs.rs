@@ -0,0 +1,136 @@
+//! Puzzle inputs found by convention in a local directory
+//!
+//! Every day has its real input and the example from the puzzle text next to
+//! each other:
+//!
+//! ```text
+//! inputs/day06.txt
+//! inputs/day06.example.txt
+//! ```
+//!
+//! Real inputs are personal and not checked in, the examples are shared with
+//! the tests of every day.
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    /// The personal puzzle input
+    Real,
+    /// The example from the puzzle text
+    Example,
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing(PathBuf),
+    Empty(PathBuf),
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Missing(path) => write!(f, "missing input {}", path.display()),
+            InputError::Empty(path) => write!(f, "empty input {}", path.display()),
+            InputError::Io(path, e) => write!(f, "can't read input {}: {e}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Directory with the inputs of all days
+#[derive(Debug, Clone)]
+pub struct InputStore {
+    dir: PathBuf,
+}
+
+impl InputStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Path of an input, for example `inputs/day06.txt` or `inputs/day06.example.txt`
+    pub fn path(&self, day: u8, kind: InputKind) -> PathBuf {
+        match kind {
+            InputKind::Real => self.dir.join(format!("day{day:02}.txt")),
+            InputKind::Example => self.dir.join(format!("day{day:02}.example.txt")),
+        }
+    }
+
+    /// Read an input of a day, failing when it's missing or empty
+    pub fn read(&self, day: u8, kind: InputKind) -> Result<String, InputError> {
+        read_path(&self.path(day, kind))
+    }
+}
+
+/// Read an input from any path, with the same checks as the store
+///
+/// The error isn't logged, callers report it the way that fits them.
+pub fn read_path(path: &Path) -> Result<String, InputError> {
+    match fs::read_to_string(path) {
+        Ok(input) if input.trim().is_empty() => Err(InputError::Empty(path.to_path_buf())),
+        Ok(input) => Ok(input),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            Err(InputError::Missing(path.to_path_buf()))
+        }
+        Err(e) => Err(InputError::Io(path.to_path_buf(), e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        let store = InputStore::new("inputs");
+        assert_eq!(
+            store.path(6, InputKind::Real),
+            PathBuf::from("inputs/day06.txt")
+        );
+        assert_eq!(
+            store.path(11, InputKind::Example),
+            PathBuf::from("inputs/day11.example.txt")
+        );
+    }
+
+    #[test]
+    fn test_read() {
+        let dir = std::env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day01.txt"), "3   4\n").unwrap();
+        fs::write(dir.join("day02.txt"), " \n").unwrap();
+        let store = InputStore::new(&dir);
+
+        assert_eq!(store.read(1, InputKind::Real).unwrap(), "3   4\n");
+        assert!(matches!(
+            store.read(2, InputKind::Real),
+            Err(InputError::Empty(_))
+        ));
+        assert!(matches!(
+            store.read(1, InputKind::Example),
+            Err(InputError::Missing(_))
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_examples_are_stored() {
+        let store = InputStore::new(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs"));
+        for solution in crate::solution::SOLUTIONS {
+            let day = solution.day();
+            let input = match store.read(day, InputKind::Example) {
+                Ok(input) => input,
+                // `new` writes an empty example for a day that was just scaffolded
+                Err(InputError::Empty(_)) => continue,
+                Err(e) => panic!("paste the example of day {day}: {e}"),
+            };
+            assert!(solution.parse(&input).is_ok(), "day {day} example");
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Advent of Code 2024 solutions
+//!
+//! Every day lives in its own module with a `parse` function and the `part1`
+//! and `part2` solutions, along with the domain types used to solve it.
+//! [`solution::SOLUTIONS`] lists all of them behind a common interface.
+pub mod answers;
+pub mod checked;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod error;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+pub mod generate;
+pub mod grid;
+pub mod inputs;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
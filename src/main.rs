@@ -1,19 +1,660 @@
-use std::io::{self, Read};
-
-mod day1;
-mod day10;
-mod day11;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-
-fn main() {
-    let mut input = String::new();
-    io::stdin().read_to_string(&mut input).unwrap();
-    println!("{}", day11::part2(&input));
+use std::{
+    fmt,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+#[cfg(feature = "fetch")]
+use aoc_rs_2024::fetch::{self, FetchError, Fetched, Fetcher};
+use aoc_rs_2024::{
+    answers::{Answers, AnswersError, Verdict},
+    day1::{self, BreakdownOrder},
+    error::{ParseError, SolveError},
+    generate::{self, GenerateError},
+    inputs::{self, InputError, InputKind, InputStore},
+    runner::{self, Row},
+    scaffold::{self, ScaffoldError},
+    solution::{self, Answer, Part, SOLUTIONS},
+};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use log::LevelFilter;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Advent of Code 2024 solutions")]
+#[cfg_attr(
+    not(feature = "fetch"),
+    command(
+        after_help = "Build with `--features fetch` to download inputs with the `fetch` command"
+    )
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Print debug output of the solutions, twice for trace output.
+    /// `RUST_LOG` overrides it, for example `RUST_LOG=aoc_rs_2024::day9=trace`
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run a single part of a single day, or every day with --all
+    Run {
+        /// Day of the puzzle to run
+        #[arg(short, long, required_unless_present = "all")]
+        day: Option<u8>,
+        /// Part of the puzzle to run
+        #[arg(short, long, required_unless_present = "all")]
+        part: Option<u8>,
+        /// Read the puzzle input from this file instead of the inputs directory, `-` for stdin
+        #[arg(short, long, conflicts_with_all = ["all", "example"])]
+        input: Option<PathBuf>,
+        /// Use the example from the puzzle text, named like `day06.example.txt`
+        #[arg(short, long)]
+        example: bool,
+        /// Run both parts of every day and print a timing table
+        #[arg(short, long, conflicts_with_all = ["day", "part"])]
+        all: bool,
+        /// Directory with the puzzle inputs, named like `day06.txt`
+        #[arg(long, default_value = "inputs")]
+        inputs_dir: PathBuf,
+        /// How to print the results
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Run every day and compare the answers with the recorded accepted answers
+    Verify {
+        /// Directory with the puzzle inputs, named like `day06.txt`
+        #[arg(long, default_value = "inputs")]
+        inputs_dir: PathBuf,
+        /// File with the accepted answers
+        #[arg(long, default_value = "answers.toml")]
+        answers: PathBuf,
+    },
+    /// Record the accepted answer of a day, by default the answer of the current solution
+    Record {
+        /// Day of the puzzle to record
+        #[arg(short, long)]
+        day: u8,
+        /// Only record this part, both parts are recorded if not set
+        #[arg(short, long)]
+        part: Option<u8>,
+        /// Record this answer instead of running the solution
+        #[arg(long, requires = "part")]
+        answer: Option<Answer>,
+        /// Directory with the puzzle inputs, named like `day06.txt`
+        #[arg(long, default_value = "inputs")]
+        inputs_dir: PathBuf,
+        /// File with the accepted answers
+        #[arg(long, default_value = "answers.toml")]
+        answers: PathBuf,
+    },
+    /// Generate and register the module of a new day
+    New {
+        /// Day of the puzzle to generate
+        #[arg(short, long)]
+        day: u8,
+        /// Root of the crate to generate the day in
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+    },
+    /// Print a random input for a day, to benchmark or stress test with bigger inputs
+    Generate {
+        /// Day of the puzzle to generate an input for
+        #[arg(short, long)]
+        day: u8,
+        /// Number of lines, or width and height of grids
+        #[arg(short, long, default_value_t = 100)]
+        size: usize,
+        /// Seed of the input, a random one is picked and printed to stderr if not set
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// List what every pair of the day 1 lists adds to the answer of a part
+    Breakdown {
+        /// Part of the puzzle to break down
+        #[arg(short, long)]
+        part: u8,
+        /// Order of the rows
+        #[arg(short, long, value_enum, default_value_t = Sort::Lists)]
+        sort: Sort,
+        /// Only print this many rows
+        #[arg(short, long)]
+        top: Option<usize>,
+        /// Read the puzzle input from this file instead of the inputs directory, `-` for stdin
+        #[arg(short, long, conflicts_with = "example")]
+        input: Option<PathBuf>,
+        /// Use the example from the puzzle text, named like `day01.example.txt`
+        #[arg(short, long)]
+        example: bool,
+        /// Directory with the puzzle inputs, named like `day01.txt`
+        #[arg(long, default_value = "inputs")]
+        inputs_dir: PathBuf,
+    },
+    /// Download the input of a day into the inputs directory, unless it's already there
+    ///
+    /// Only built with `--features fetch`, it pulls in an HTTP and TLS stack.
+    #[cfg(feature = "fetch")]
+    Fetch {
+        /// Day of the puzzle to download
+        #[arg(short, long)]
+        day: u8,
+        /// Value of the `session` cookie of a browser logged in to the puzzle site
+        #[arg(long, env = "AOC_SESSION", hide_env_values = true)]
+        session: String,
+        /// Site to download from, for example a local stand-in to test with
+        #[arg(long, env = "AOC_BASE_URL", default_value = fetch::DEFAULT_BASE_URL)]
+        base_url: String,
+        /// Directory with the puzzle inputs, named like `day06.txt`
+        #[arg(long, default_value = "inputs")]
+        inputs_dir: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    /// The bare answer, or a table with --all
+    Text,
+    /// A JSON array with the answer, timings and errors of every part
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Sort {
+    /// The order of the sorted lists
+    Lists,
+    /// Largest distance or contribution first
+    Largest,
+}
+
+impl From<Sort> for BreakdownOrder {
+    fn from(value: Sort) -> Self {
+        match value {
+            Sort::Lists => BreakdownOrder::Lists,
+            Sort::Largest => BreakdownOrder::Largest,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum RunError {
+    UnknownDay(u8),
+    UnknownPart(u8),
+    Io(io::Error),
+    Input(InputError),
+    Parse {
+        day: u8,
+        error: ParseError,
+        snippet: Option<String>,
+    },
+    Solve {
+        day: u8,
+        part: Part,
+        error: SolveError,
+    },
+    Answers(AnswersError),
+    Scaffold(ScaffoldError),
+    Generate(GenerateError),
+    #[cfg(feature = "fetch")]
+    Fetch(FetchError),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::UnknownDay(day) => write!(f, "day {day} is not implemented"),
+            RunError::UnknownPart(part) => write!(f, "part {part} does not exist, use 1 or 2"),
+            RunError::Io(e) => write!(f, "failed to read input: {e}"),
+            RunError::Input(e) => write!(f, "{e}"),
+            RunError::Parse {
+                day,
+                error,
+                snippet,
+            } => {
+                write!(f, "day {day}: invalid input at {error}")?;
+                if let Some(snippet) = snippet {
+                    write!(f, "\n{snippet}")?;
+                }
+                Ok(())
+            }
+            RunError::Solve { day, part, error } => {
+                write!(f, "day {day} part {part}: {error}")
+            }
+            RunError::Answers(e) => write!(f, "invalid answers file: {e}"),
+            RunError::Scaffold(e) => write!(f, "failed to generate the day: {e}"),
+            RunError::Generate(e) => write!(f, "failed to generate the input: {e}"),
+            #[cfg(feature = "fetch")]
+            RunError::Fetch(e) => write!(f, "failed to fetch the input: {e}"),
+        }
+    }
+}
+
+impl From<InputError> for RunError {
+    fn from(value: InputError) -> Self {
+        Self::Input(value)
+    }
+}
+
+impl From<io::Error> for RunError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl RunError {
+    fn parse(day: u8, error: ParseError, input: &str) -> Self {
+        Self::Parse {
+            day,
+            snippet: error.snippet(input),
+            error,
+        }
+    }
+}
+
+impl From<AnswersError> for RunError {
+    fn from(value: AnswersError) -> Self {
+        Self::Answers(value)
+    }
+}
+
+impl From<ScaffoldError> for RunError {
+    fn from(value: ScaffoldError) -> Self {
+        Self::Scaffold(value)
+    }
+}
+
+impl From<GenerateError> for RunError {
+    fn from(value: GenerateError) -> Self {
+        Self::Generate(value)
+    }
+}
+
+#[cfg(feature = "fetch")]
+impl From<FetchError> for RunError {
+    fn from(value: FetchError) -> Self {
+        Self::Fetch(value)
+    }
+}
+
+/// Read the input given with --input, or find it in the store by convention
+fn read_input(
+    path: Option<PathBuf>,
+    store: &InputStore,
+    day: u8,
+    kind: InputKind,
+) -> Result<String, RunError> {
+    match path {
+        Some(path) if path == Path::new("-") => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+        Some(path) => Ok(inputs::read_path(&path)?),
+        None => Ok(store.read(day, kind)?),
+    }
+}
+
+/// Fails when a day was skipped or a part has no answer, so scripts can rely on the exit code
+fn run_all(store: &InputStore, kind: InputKind, format: Format) -> ExitCode {
+    let mut rows = Vec::new();
+    for solution in SOLUTIONS {
+        let results = store
+            .read(solution.day(), kind)
+            .map_err(|e| e.to_string())
+            .and_then(|input| {
+                runner::run_day(*solution, &input, &Part::ALL)
+                    .map_err(|e| format!("invalid input at {e}"))
+            });
+        match results {
+            Ok(results) => rows.extend(results.into_iter().map(Row::Solved)),
+            Err(reason) => rows.push(Row::Skipped {
+                day: solution.day(),
+                reason,
+            }),
+        }
+    }
+
+    match format {
+        Format::Text => print!("{}", runner::render_table(&rows)),
+        Format::Json => println!("{}", runner::render_json(&rows)),
+    }
+
+    let solved = |row: &Row| matches!(row, Row::Solved(result) if result.answer.is_ok());
+    if rows.iter().all(solved) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Returns whether all answers that were recorded still match
+fn verify(store: &InputStore, answers: &Path) -> Result<bool, RunError> {
+    let answers = Answers::load(answers)?;
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for solution in SOLUTIONS {
+        let day = solution.day();
+        let input = match store.read(day, InputKind::Real) {
+            Ok(input) => input,
+            Err(reason) => {
+                println!("day {day:02}: MISSING ({reason})");
+                missing += Part::ALL.len();
+                continue;
+            }
+        };
+
+        let results = match runner::run_day(*solution, &input, &Part::ALL) {
+            Ok(results) => results,
+            Err(e) => {
+                // the input was accepted before, so not parsing anymore is a regression
+                println!("day {day:02}: FAIL (invalid input at {e})");
+                failed += Part::ALL.len();
+                continue;
+            }
+        };
+
+        for result in results {
+            let answer = match result.answer {
+                Ok(answer) => answer,
+                Err(e) => {
+                    println!("day {day:02} part {}: FAIL ({e})", result.part);
+                    failed += 1;
+                    continue;
+                }
+            };
+            let verdict = Verdict::new(answers.get(day, result.part), &answer);
+            match &verdict {
+                Verdict::Pass => passed += 1,
+                Verdict::Fail { expected } => {
+                    failed += 1;
+                    println!(
+                        "day {day:02} part {}: {verdict} (expected {expected}, got {answer})",
+                        result.part
+                    );
+                    continue;
+                }
+                Verdict::Missing => missing += 1,
+            }
+            println!("day {day:02} part {}: {verdict}", result.part);
+        }
+    }
+
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    Ok(failed == 0)
+}
+
+fn record(
+    day: u8,
+    part: Option<u8>,
+    answer: Option<Answer>,
+    store: &InputStore,
+    answers_path: &Path,
+) -> Result<(), RunError> {
+    let solution = solution::find(day).ok_or(RunError::UnknownDay(day))?;
+    let parts = match part {
+        Some(part) => vec![Part::try_from(part).map_err(RunError::UnknownPart)?],
+        None => Part::ALL.to_vec(),
+    };
+
+    let mut answers = Answers::load(answers_path)?;
+    let recorded = match answer {
+        // clap makes sure there is a single part when the answer is given
+        Some(answer) => vec![(parts[0], answer)],
+        None => {
+            let input = store.read(day, InputKind::Real)?;
+            runner::run_day(solution, &input, &parts)
+                .map_err(|e| RunError::parse(day, e, &input))?
+                .into_iter()
+                .map(|result| match result.answer {
+                    Ok(answer) => Ok((result.part, answer)),
+                    Err(error) => Err(RunError::Solve {
+                        day,
+                        part: result.part,
+                        error,
+                    }),
+                })
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    for (part, answer) in recorded {
+        println!("day {day:02} part {part}: {answer}");
+        answers.set(day, part, answer);
+    }
+    answers.save(answers_path)?;
+
+    Ok(())
+}
+
+/// Print the rows of a day 1 part, so it's clear which ones dominate the answer
+fn breakdown(
+    input: &str,
+    part: Part,
+    order: BreakdownOrder,
+    top: Option<usize>,
+) -> Result<(), RunError> {
+    let lists = day1::parse(input).map_err(|e| RunError::parse(1, e, input))?;
+    let (headers, rows): (&[&str], Vec<Vec<String>>) = match part {
+        Part::One => (
+            &["First", "Second", "Distance"],
+            day1::pair_distances(&lists, order, top)
+                .into_iter()
+                .map(|pair| {
+                    vec![
+                        pair.first.to_string(),
+                        pair.second.to_string(),
+                        pair.distance.to_string(),
+                    ]
+                })
+                .collect(),
+        ),
+        Part::Two => (
+            &["Value", "Count", "Occurrences", "Contribution"],
+            day1::similarity_breakdown(&lists, order, top)
+                .map_err(|error| RunError::Solve {
+                    day: 1,
+                    part,
+                    error,
+                })?
+                .into_iter()
+                .map(|similarity| {
+                    vec![
+                        similarity.value.to_string(),
+                        similarity.count.to_string(),
+                        similarity.occurrences.to_string(),
+                        similarity.contribution.to_string(),
+                    ]
+                })
+                .collect(),
+        ),
+    };
+
+    let mut widths: Vec<_> = headers.iter().map(|header| header.len()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let print_row = |row: &[&str]| {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:>width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{line}");
+    };
+    print_row(headers);
+    for row in &rows {
+        print_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+
+    Ok(())
+}
+
+fn input_kind(example: bool) -> InputKind {
+    if example {
+        InputKind::Example
+    } else {
+        InputKind::Real
+    }
+}
+
+fn run(cli: Cli) -> Result<ExitCode, RunError> {
+    match cli.command {
+        Command::Run {
+            all: true,
+            inputs_dir,
+            example,
+            format,
+            ..
+        } => {
+            return Ok(run_all(
+                &InputStore::new(inputs_dir),
+                input_kind(example),
+                format,
+            ))
+        }
+        Command::Run {
+            day,
+            part,
+            input,
+            example,
+            inputs_dir,
+            format,
+            ..
+        } => {
+            // clap makes sure both are present without --all
+            let (Some(day), Some(part)) = (day, part) else {
+                unreachable!("day and part are required without --all");
+            };
+            let solution = solution::find(day).ok_or(RunError::UnknownDay(day))?;
+            let part = Part::try_from(part).map_err(RunError::UnknownPart)?;
+            let store = InputStore::new(inputs_dir);
+            let input = read_input(input, &store, day, input_kind(example))?;
+            match format {
+                Format::Text => {
+                    let parsed = solution
+                        .parse(&input)
+                        .map_err(|e| RunError::parse(day, e, &input))?;
+                    let answer =
+                        parsed
+                            .solve(part)
+                            .map_err(|error| RunError::Solve { day, part, error })?;
+                    println!("{answer}");
+                }
+                Format::Json => {
+                    // scripts get the error in the output, like with --all
+                    let (rows, code) = match runner::run_day(solution, &input, &[part]) {
+                        Ok(results) => {
+                            let code = if results.iter().all(|result| result.answer.is_ok()) {
+                                ExitCode::SUCCESS
+                            } else {
+                                ExitCode::FAILURE
+                            };
+                            (results.into_iter().map(Row::Solved).collect(), code)
+                        }
+                        Err(e) => (
+                            vec![Row::Skipped {
+                                day,
+                                reason: format!("invalid input at {e}"),
+                            }],
+                            ExitCode::FAILURE,
+                        ),
+                    };
+                    println!("{}", runner::render_json(&rows));
+                    return Ok(code);
+                }
+            }
+        }
+        Command::Verify {
+            inputs_dir,
+            answers,
+        } => {
+            if !verify(&InputStore::new(inputs_dir), &answers)? {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Record {
+            day,
+            part,
+            answer,
+            inputs_dir,
+            answers,
+        } => record(day, part, answer, &InputStore::new(inputs_dir), &answers)?,
+        Command::New { day, root } => {
+            for path in scaffold::new_day(&root, day)? {
+                println!("wrote {}", path.display());
+            }
+            println!("paste the example of the puzzle into the example input to get started");
+            println!(
+                "your puzzle input goes in {}, it's not created",
+                InputStore::new(root.join("inputs"))
+                    .path(day, InputKind::Real)
+                    .display()
+            );
+        }
+        Command::Generate { day, size, seed } => {
+            let seed = seed.unwrap_or_else(|| {
+                let seed = rand::random();
+                eprintln!("seed: {seed}");
+                seed
+            });
+            print!("{}", generate::generate(day, size, seed)?);
+        }
+        Command::Breakdown {
+            part,
+            sort,
+            top,
+            input,
+            example,
+            inputs_dir,
+        } => {
+            let part = Part::try_from(part).map_err(RunError::UnknownPart)?;
+            let store = InputStore::new(inputs_dir);
+            let input = read_input(input, &store, 1, input_kind(example))?;
+            breakdown(&input, part, sort.into(), top)?;
+        }
+        #[cfg(feature = "fetch")]
+        Command::Fetch {
+            day,
+            session,
+            base_url,
+            inputs_dir,
+        } => {
+            let fetcher = Fetcher::new(base_url, session);
+            match fetcher.fetch(&InputStore::new(inputs_dir), day)? {
+                (path, Fetched::Downloaded) => println!("downloaded {}", path.display()),
+                (path, Fetched::Cached) => println!("{} is already downloaded", path.display()),
+            }
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Debug output is off unless asked for, as writing it dominates the timings
+fn init_logging(verbose: u8) {
+    let level = match verbose {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    env_logger::Builder::new()
+        .filter_level(level)
+        .parse_default_env()
+        .format_timestamp(None)
+        .init();
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    init_logging(cli.verbose);
+    match run(cli) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,258 @@
+//! Run registered solutions and time each step
+use std::{
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::{
+    error::{ParseError, SolveError},
+    solution::{Answer, AnySolution, Part},
+};
+
+#[derive(Debug)]
+pub struct PartResult {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Result<Answer, SolveError>,
+    /// Parsing is shared between both parts of a day, so this is the same for both
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+/// Parse the input once and solve the given parts from it
+pub fn run_day(
+    solution: &dyn AnySolution,
+    input: &str,
+    parts: &[Part],
+) -> Result<Vec<PartResult>, ParseError> {
+    let start = Instant::now();
+    let parsed = solution.parse(input)?;
+    let parse_time = start.elapsed();
+
+    let results = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = parsed.solve(part);
+            let solve_time = start.elapsed();
+            PartResult {
+                day: solution.day(),
+                part,
+                answer,
+                parse_time,
+                solve_time,
+            }
+        })
+        .collect();
+
+    Ok(results)
+}
+
+/// A single row of the timing table, either a result or a day we couldn't run
+#[derive(Debug)]
+pub enum Row {
+    Solved(PartResult),
+    Skipped { day: u8, reason: String },
+}
+
+/// Render the rows as a table with a line for the total time at the end
+pub fn render_table(rows: &[Row]) -> String {
+    let headers = ["Day", "Part", "Answer", "Parse", "Solve"].map(String::from);
+    let mut cells: Vec<[String; 5]> = vec![headers];
+    let mut total = Duration::ZERO;
+    for row in rows {
+        match row {
+            Row::Solved(result) => {
+                // parsing is shared between the parts, only count it once
+                let parse = if result.part == Part::One {
+                    total += result.parse_time;
+                    format!("{:.3?}", result.parse_time)
+                } else {
+                    String::new()
+                };
+                total += result.solve_time;
+                cells.push([
+                    result.day.to_string(),
+                    result.part.to_string(),
+                    match &result.answer {
+                        Ok(answer) => answer.to_string(),
+                        Err(e) => format!("error: {e}"),
+                    },
+                    parse,
+                    format!("{:.3?}", result.solve_time),
+                ]);
+            }
+            Row::Skipped { day, reason } => cells.push([
+                day.to_string(),
+                "-".to_string(),
+                reason.clone(),
+                String::new(),
+                String::new(),
+            ]),
+        }
+    }
+
+    let mut widths = [0; 5];
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    cells.insert(1, widths.map(|width| "-".repeat(width)));
+
+    let mut table = String::new();
+    for row in &cells {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        // writing to a string can't fail
+        let _ = writeln!(table, "{}", line.trim_end());
+    }
+    let _ = writeln!(table, "Total: {total:.3?}");
+
+    table
+}
+
+/// A row of the JSON output, fields that don't apply to the row are `null`
+#[derive(Debug, Serialize)]
+struct JsonRow {
+    day: u8,
+    part: Option<u8>,
+    /// Answers are always strings so big numbers don't lose precision
+    answer: Option<String>,
+    /// `integer` or `text`
+    answer_type: Option<&'static str>,
+    /// Parsing is shared between both parts of a day, so this is the same for both
+    parse_ns: Option<u64>,
+    solve_ns: Option<u64>,
+    error: Option<String>,
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos().try_into().unwrap_or(u64::MAX)
+}
+
+/// Render the rows as a JSON array, for scripts instead of humans
+pub fn render_json(rows: &[Row]) -> String {
+    let rows: Vec<_> = rows
+        .iter()
+        .map(|row| match row {
+            Row::Solved(result) => JsonRow {
+                day: result.day,
+                part: Some(result.part.number()),
+                answer: result.answer.as_ref().ok().map(Answer::to_string),
+                answer_type: result.answer.as_ref().ok().map(Answer::kind),
+                parse_ns: Some(nanos(result.parse_time)),
+                solve_ns: Some(nanos(result.solve_time)),
+                error: result.answer.as_ref().err().map(SolveError::to_string),
+            },
+            Row::Skipped { day, reason } => JsonRow {
+                day: *day,
+                part: None,
+                answer: None,
+                answer_type: None,
+                parse_ns: None,
+                solve_ns: None,
+                error: Some(reason.clone()),
+            },
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&rows).expect("rows only contain strings and numbers")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_table() {
+        let rows = vec![
+            Row::Solved(PartResult {
+                day: 1,
+                part: Part::One,
+                answer: Ok("11".parse().unwrap()),
+                parse_time: Duration::from_millis(1),
+                solve_time: Duration::from_millis(2),
+            }),
+            Row::Solved(PartResult {
+                day: 1,
+                part: Part::Two,
+                answer: Ok("31".parse().unwrap()),
+                parse_time: Duration::from_millis(1),
+                solve_time: Duration::from_millis(3),
+            }),
+            Row::Skipped {
+                day: 2,
+                reason: "missing input".to_string(),
+            },
+        ];
+
+        let table = render_table(&rows);
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines[0], "Day | Part | Answer        | Parse   | Solve");
+        assert_eq!(lines[2], "1   | 1    | 11            | 1.000ms | 2.000ms");
+        assert_eq!(lines[3], "1   | 2    | 31            |         | 3.000ms");
+        assert_eq!(lines[4], "2   | -    | missing input |         |");
+        assert_eq!(lines[5], "Total: 6.000ms");
+    }
+
+    #[test]
+    fn test_render_json() {
+        let rows = vec![
+            Row::Solved(PartResult {
+                day: 3,
+                part: Part::Two,
+                answer: Ok("48".parse().unwrap()),
+                parse_time: Duration::from_micros(1),
+                solve_time: Duration::from_micros(2),
+            }),
+            Row::Solved(PartResult {
+                day: 4,
+                part: Part::One,
+                answer: Ok("ABC".parse().unwrap()),
+                parse_time: Duration::ZERO,
+                solve_time: Duration::ZERO,
+            }),
+            Row::Solved(PartResult {
+                day: 4,
+                part: Part::Two,
+                answer: Err(SolveError::new("1 + 1 overflows u8").on_line(3)),
+                parse_time: Duration::ZERO,
+                solve_time: Duration::ZERO,
+            }),
+            Row::Skipped {
+                day: 5,
+                reason: "missing input".to_string(),
+            },
+        ];
+
+        let json: serde_json::Value = serde_json::from_str(&render_json(&rows)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {
+                    "day": 3, "part": 2, "answer": "48", "answer_type": "integer",
+                    "parse_ns": 1000, "solve_ns": 2000, "error": null
+                },
+                {
+                    "day": 4, "part": 1, "answer": "ABC", "answer_type": "text",
+                    "parse_ns": 0, "solve_ns": 0, "error": null
+                },
+                {
+                    "day": 4, "part": 2, "answer": null, "answer_type": null,
+                    "parse_ns": 0, "solve_ns": 0, "error": "line 3: 1 + 1 overflows u8"
+                },
+                {
+                    "day": 5, "part": null, "answer": null, "answer_type": null,
+                    "parse_ns": null, "solve_ns": null, "error": "missing input"
+                },
+            ])
+        );
+    }
+}
@@ -0,0 +1,331 @@
+//! Generate the module of a new day, in the same shape as the existing days
+//!
+//! `new --day 12` writes `src/day12.rs`, declares it in `src/lib.rs`, adds it to
+//! [`crate::solution::SOLUTIONS`] and creates an empty `inputs/day12.example.txt`
+//! for the example of the puzzle text. The real input isn't created, it's not
+//! committed and has to be downloaded or pasted into `inputs/day12.txt`.
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::inputs::{InputKind, InputStore};
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    InvalidDay(u8),
+    AlreadyExists(PathBuf),
+    /// A file we edit doesn't look like we expect anymore
+    Unrecognized {
+        path: PathBuf,
+        reason: String,
+    },
+    Io(io::Error),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldError::InvalidDay(day) => write!(f, "day {day} is not between 1 and 25"),
+            ScaffoldError::AlreadyExists(path) => write!(f, "{} already exists", path.display()),
+            ScaffoldError::Unrecognized { path, reason } => {
+                write!(f, "can't register the day in {}: {reason}", path.display())
+            }
+            ScaffoldError::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+impl From<io::Error> for ScaffoldError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Source of a new day module, answers are 0 until it's solved
+pub fn day_module(day: u8) -> String {
+    format!(
+        r#"use crate::{{
+    error::{{self, ParseError, SolveError}},
+    solution::Solution,
+}};
+
+pub fn parse(input: &str) -> Result<Vec<String>, ParseError> {{
+    Ok(error::lines(input)
+        .map(|(_, line)| line.to_string())
+        .collect())
+}}
+
+pub fn part1(_lines: &[String]) -> Result<u64, SolveError> {{
+    Ok(0)
+}}
+
+pub fn part2(_lines: &[String]) -> Result<u64, SolveError> {{
+    Ok(0)
+}}
+
+pub struct Day{day};
+
+impl Solution for Day{day} {{
+    const DAY: u8 = {day};
+
+    type Input = Vec<String>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {{
+        parse(input)
+    }}
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError> {{
+        part1(input)
+    }}
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError> {{
+        part2(input)
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    const INPUT: &str = include_str!("../inputs/day{day:02}.example.txt");
+
+    #[test]
+    fn test_part1() {{
+        // the answer of the example in the puzzle text
+        assert_eq!(part1(&parse(INPUT).unwrap()), Ok(0));
+    }}
+
+    #[test]
+    fn test_part2() {{
+        assert_eq!(part2(&parse(INPUT).unwrap()), Ok(0));
+    }}
+}}
+"#
+    )
+}
+
+fn unrecognized(path: &str, reason: &str) -> ScaffoldError {
+    ScaffoldError::Unrecognized {
+        path: PathBuf::from(path),
+        reason: reason.to_string(),
+    }
+}
+
+/// Add `pub mod dayN;` to the source of `lib.rs`, keeping the modules sorted like rustfmt
+pub fn register_module(lib_rs: &str, day: u8) -> Result<String, ScaffoldError> {
+    let module = format!("day{day}");
+    let declared = |line: &str| {
+        line.strip_prefix("pub mod ")
+            .and_then(|rest| rest.strip_suffix(';'))
+            .map(str::to_string)
+    };
+    if lib_rs
+        .lines()
+        .any(|line| declared(line).as_ref() == Some(&module))
+    {
+        return Err(unrecognized("src/lib.rs", "the module is already declared"));
+    }
+
+    let declaration = format!("pub mod {module};");
+    let mut lines: Vec<&str> = lib_rs.lines().collect();
+    let index = lines
+        .iter()
+        .position(|line| declared(line).is_some_and(|name| name > module))
+        .or_else(|| {
+            lines
+                .iter()
+                .rposition(|line| declared(line).is_some())
+                .map(|i| i + 1)
+        })
+        .ok_or_else(|| unrecognized("src/lib.rs", "there are no `pub mod` declarations"))?;
+    lines.insert(index, &declaration);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Pack the items of a `use crate::{...}` like rustfmt does, in as few lines as possible
+fn format_crate_use(items: &[String]) -> String {
+    let single = format!("use crate::{{{}}};", items.join(", "));
+    if single.len() <= 100 {
+        return single;
+    }
+
+    let mut lines = vec![String::from("   ")];
+    for item in items {
+        let last = lines.last_mut().expect("there is always a line");
+        if last.len() + 1 + item.len() + 1 > 100 {
+            lines.push(format!("    {item},"));
+        } else {
+            last.push_str(&format!(" {item},"));
+        }
+    }
+    format!("use crate::{{\n{}\n}};", lines.join("\n"))
+}
+
+/// Split the items of a `use` on the commas that aren't inside of nested braces
+fn split_use_items(items: &str) -> Vec<String> {
+    let mut split = Vec::new();
+    let (mut depth, mut item_start) = (0, 0);
+    for (i, c) in items.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                split.push(&items[item_start..i]);
+                item_start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    split.push(&items[item_start..]);
+
+    split
+        .into_iter()
+        .map(|item| item.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Import `dayN` and add it to `SOLUTIONS` in the source of `solution.rs`
+pub fn register_solution(solution_rs: &str, day: u8) -> Result<String, ScaffoldError> {
+    const PATH: &str = "src/solution.rs";
+
+    let start = solution_rs
+        .find("use crate::{")
+        .ok_or_else(|| unrecognized(PATH, "missing `use crate::{...}`"))?;
+    let end = start
+        + solution_rs[start..]
+            .find("};")
+            .ok_or_else(|| unrecognized(PATH, "unterminated `use crate::{...}`"))?
+        + 2;
+    let mut items = split_use_items(&solution_rs[start + "use crate::{".len()..end - 2]);
+    let module = format!("day{day}");
+    if items.contains(&module) {
+        return Err(unrecognized(PATH, "the day is already imported"));
+    }
+    items.push(module);
+    items.sort();
+    let source = format!(
+        "{}{}{}",
+        &solution_rs[..start],
+        format_crate_use(&items),
+        &solution_rs[end..]
+    );
+
+    // entries are sorted by day, so the new one goes before the first later day
+    let registry_start = source
+        .find("pub static SOLUTIONS")
+        .ok_or_else(|| unrecognized(PATH, "missing `SOLUTIONS`"))?;
+    let registry_end = registry_start
+        + source[registry_start..]
+            .find("];")
+            .ok_or_else(|| unrecognized(PATH, "unterminated `SOLUTIONS`"))?;
+    let entry = format!("    &Registered::<day{day}::Day{day}>::new(),\n");
+    let mut insert_at = registry_end;
+    let mut offset = registry_start;
+    for line in source[registry_start..registry_end].split_inclusive('\n') {
+        let entry_day = line
+            .trim()
+            .strip_prefix("&Registered::<day")
+            .and_then(|rest| rest.split(':').next())
+            .and_then(|number| number.parse::<u8>().ok());
+        if entry_day.is_some_and(|entry_day| entry_day > day) {
+            insert_at = offset;
+            break;
+        }
+        offset += line.len();
+    }
+
+    Ok(format!(
+        "{}{entry}{}",
+        &source[..insert_at],
+        &source[insert_at..]
+    ))
+}
+
+/// Create and register a new day in the crate at `root`, returning the files it wrote
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>, ScaffoldError> {
+    if !(1..=25).contains(&day) {
+        return Err(ScaffoldError::InvalidDay(day));
+    }
+
+    let module_path = root.join(format!("src/day{day}.rs"));
+    if module_path.exists() {
+        return Err(ScaffoldError::AlreadyExists(module_path));
+    }
+
+    // edit everything in memory first, so nothing is half registered on errors
+    let lib_path = root.join("src/lib.rs");
+    let lib_rs = register_module(&fs::read_to_string(&lib_path)?, day)?;
+    let solution_path = root.join("src/solution.rs");
+    let solution_rs = register_solution(&fs::read_to_string(&solution_path)?, day)?;
+
+    let example_path = InputStore::new(root.join("inputs")).path(day, InputKind::Example);
+    if let Some(dir) = example_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    if !example_path.exists() {
+        fs::write(&example_path, "")?;
+    }
+    fs::write(&module_path, day_module(day))?;
+    fs::write(&lib_path, lib_rs)?;
+    fs::write(&solution_path, solution_rs)?;
+
+    Ok(vec![module_path, lib_path, solution_path, example_path])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_module() {
+        let lib_rs = "//! docs\npub mod day1;\npub mod day10;\npub mod day2;\npub mod error;\n";
+        assert_eq!(
+            register_module(lib_rs, 12).unwrap(),
+            "//! docs\npub mod day1;\npub mod day10;\npub mod day12;\npub mod day2;\npub mod error;\n"
+        );
+        assert!(register_module(lib_rs, 2).is_err());
+    }
+
+    #[test]
+    fn test_register_solution() {
+        let solution_rs = "use crate::{\n    \
+            day1, day10, day11, day2, day3, day4, day5, day6, day7, day8, day9, error::ParseError,\n\
+            };\n\n\
+            pub static SOLUTIONS: &[&dyn AnySolution] = &[\n    \
+            &Registered::<day11::Day11>::new(),\n];\n";
+        let registered = register_solution(solution_rs, 12).unwrap();
+        assert_eq!(
+            registered,
+            "use crate::{\n    \
+            day1, day10, day11, day12, day2, day3, day4, day5, day6, day7, day8, day9, error::ParseError,\n\
+            };\n\n\
+            pub static SOLUTIONS: &[&dyn AnySolution] = &[\n    \
+            &Registered::<day11::Day11>::new(),\n    \
+            &Registered::<day12::Day12>::new(),\n];\n"
+        );
+        assert!(register_solution(&registered, 12).is_err());
+    }
+
+    #[test]
+    fn test_register_solution_in_the_middle() {
+        let solution_rs = "use crate::{day1, day3, error::ParseError};\n\n\
+            pub static SOLUTIONS: &[&dyn AnySolution] = &[\n    \
+            &Registered::<day1::Day1>::new(),\n    \
+            &Registered::<day3::Day3>::new(),\n];\n";
+        assert_eq!(
+            register_solution(solution_rs, 2).unwrap(),
+            "use crate::{day1, day2, day3, error::ParseError};\n\n\
+            pub static SOLUTIONS: &[&dyn AnySolution] = &[\n    \
+            &Registered::<day1::Day1>::new(),\n    \
+            &Registered::<day2::Day2>::new(),\n    \
+            &Registered::<day3::Day3>::new(),\n];\n"
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Common interface shared by all days, and the registry of implemented days
+use std::{convert::Infallible, fmt::Display, marker::PhantomData, str::FromStr};
+
+use crate::{
+    day1, day10, day11, day2, day3, day4, day5, day6, day7, day8, day9,
+    error::{ParseError, SolveError},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            other => Err(other),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// Answer of a single part, every day converts its own answer type into it
+///
+/// Integers are compared by value, everything else like a comma separated list
+/// is kept as text.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+}
+
+impl Answer {
+    /// Name of the variant, for output read by scripts
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Integer(_) => "integer",
+            Answer::Text(_) => "text",
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(answer) => write!(f, "{answer}"),
+            Answer::Text(answer) => write!(f, "{answer}"),
+        }
+    }
+}
+
+/// Text that is exactly how an integer is printed is an integer, anything else is text
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.parse::<i128>() {
+            Ok(answer) if answer.to_string() == s => Answer::Integer(answer),
+            _ => Answer::Text(s.to_string()),
+        })
+    }
+}
+
+macro_rules! answer_from_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl From<$integer> for Answer {
+                fn from(value: $integer) -> Self {
+                    Answer::Integer(value.into())
+                }
+            }
+        )*
+    };
+}
+
+answer_from_integer!(u8, u16, u32, u64, i8, i16, i32, i64, i128);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Integer(value as i128)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        let Ok(answer) = value.parse();
+        answer
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
+
+/// A solution for a single day
+///
+/// The input is parsed once into [`Solution::Input`] and then both parts are solved
+/// from the same parsed input.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+    type Answer1: Into<Answer>;
+    type Answer2: Into<Answer>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, SolveError>;
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, SolveError>;
+}
+
+/// Object safe version of [`Solution`] so all days can live in a single registry
+pub trait AnySolution: Sync {
+    fn day(&self) -> u8;
+    fn parse(&self, input: &str) -> Result<Box<dyn ParsedInput>, ParseError>;
+}
+
+/// Input that was already parsed by an [`AnySolution`], ready to be solved
+pub trait ParsedInput {
+    fn solve(&self, part: Part) -> Result<Answer, SolveError>;
+}
+
+struct Registered<S>(PhantomData<fn() -> S>);
+
+impl<S> Registered<S> {
+    const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+struct Parsed<S: Solution>(S::Input);
+
+impl<S: Solution> ParsedInput for Parsed<S> {
+    fn solve(&self, part: Part) -> Result<Answer, SolveError> {
+        match part {
+            Part::One => S::part1(&self.0).map(Into::into),
+            Part::Two => S::part2(&self.0).map(Into::into),
+        }
+    }
+}
+
+impl<S> AnySolution for Registered<S>
+where
+    S: Solution + 'static,
+    S::Input: 'static,
+{
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn ParsedInput>, ParseError> {
+        Ok(Box::new(Parsed::<S>(S::parse(input)?)))
+    }
+}
+
+/// All implemented days, sorted by day
+pub static SOLUTIONS: &[&dyn AnySolution] = &[
+    &Registered::<day1::Day1>::new(),
+    &Registered::<day2::Day2>::new(),
+    &Registered::<day3::Day3>::new(),
+    &Registered::<day4::Day4>::new(),
+    &Registered::<day5::Day5>::new(),
+    &Registered::<day6::Day6>::new(),
+    &Registered::<day7::Day7>::new(),
+    &Registered::<day8::Day8>::new(),
+    &Registered::<day9::Day9>::new(),
+    &Registered::<day10::Day10>::new(),
+    &Registered::<day11::Day11>::new(),
+];
+
+pub fn find(day: u8) -> Option<&'static dyn AnySolution> {
+    SOLUTIONS
+        .iter()
+        .find(|solution| solution.day() == day)
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_sorted_and_unique() {
+        let days: Vec<u8> = SOLUTIONS.iter().map(|solution| solution.day()).collect();
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]), "{days:?}");
+    }
+
+    #[test]
+    fn test_part_from_number() {
+        assert_eq!(Part::try_from(1), Ok(Part::One));
+        assert_eq!(Part::try_from(2), Ok(Part::Two));
+        assert_eq!(Part::try_from(3), Err(3));
+    }
+
+    #[test]
+    fn test_answer() {
+        assert_eq!(Answer::from(31_u16), Answer::Integer(31));
+        assert_eq!(Answer::from(usize::MAX).to_string(), usize::MAX.to_string());
+        assert_eq!("-12".parse(), Ok(Answer::Integer(-12)));
+        assert_eq!("012".parse(), Ok(Answer::Text("012".to_string())));
+        assert_eq!(Answer::from("6,0,1"), Answer::Text("6,0,1".to_string()));
+        assert_eq!(Answer::from("41".to_string()), Answer::Integer(41));
+        assert!(Answer::Integer(9) < Answer::Integer(10));
+    }
+}
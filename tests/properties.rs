@@ -0,0 +1,145 @@
+//! Check the optimized solutions against simple brute force ones on random inputs
+use std::collections::HashMap;
+
+use aoc_rs_2024::{
+    day1::{self, Counting},
+    day11::{self, Stones},
+    day2::{self, Report},
+    day9::{self, DiskEntry, MemoryMap},
+};
+use proptest::prelude::*;
+
+/// Levels that mostly change by a few steps, so a good share of the reports is safe
+fn report() -> impl Strategy<Value = Report> {
+    (0u8..50, prop::collection::vec(-4i8..=4, 0..8)).prop_map(|(start, steps)| {
+        let mut levels = vec![start];
+        for step in steps {
+            let last = *levels.last().unwrap();
+            levels.push(last.saturating_add_signed(step));
+        }
+        let line = levels
+            .iter()
+            .map(u8::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        Report::from_line(&line).unwrap()
+    })
+}
+
+/// Try every level to remove instead of being clever about which one
+fn is_safe_brute_force(report: &Report) -> bool {
+    let levels = report.levels();
+    report.is_safe()
+        || (0..levels.len()).any(|skip| {
+            let line = levels
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != skip)
+                .map(|(_, level)| level.to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            Report::from_line(&line).unwrap().is_safe()
+        })
+}
+
+/// A dense disk map, files take at least one block but free space can be empty
+fn disk_map() -> impl Strategy<Value = String> {
+    prop::collection::vec((1u8..=9, 0u8..=9), 1..30).prop_map(|entries| {
+        entries
+            .into_iter()
+            .flat_map(|(file, free)| [file, free])
+            .map(|digit| char::from(b'0' + digit))
+            .collect()
+    })
+}
+
+/// Move whole files block by block on the expanded disk, like the puzzle text describes it
+fn compact_files_brute_force(packed: &[u8]) -> usize {
+    let mut entries = MemoryMap::from_packed(packed).entries().to_vec();
+    let last_id = packed.len().div_ceil(2) - 1;
+    for id in (0..=last_id as u16).rev() {
+        let file = DiskEntry::File { id };
+        let start = entries.iter().position(|entry| *entry == file).unwrap();
+        let size = entries[start..]
+            .iter()
+            .take_while(|entry| **entry == file)
+            .count();
+        let free = entries[..start]
+            .windows(size)
+            .position(|window| window.iter().all(|entry| *entry == DiskEntry::Free));
+        if let Some(free) = free {
+            entries[free..free + size].fill(file);
+            entries[start..start + size].fill(DiskEntry::Free);
+        }
+    }
+
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| match entry {
+            DiskEntry::Free => 0,
+            DiskEntry::File { id } => i * *id as usize,
+        })
+        .sum()
+}
+
+proptest! {
+    #[test]
+    fn test_sparse_counting_matches_dense(
+        pairs in prop::collection::vec((0u32..50, 0u32..50), 0..100),
+        offset in 0u32..4_000_000_000,
+    ) {
+        let input: String = pairs
+            .iter()
+            .map(|(first, second)| format!("{}   {}\n", first + offset, second + offset))
+            .collect();
+        let lists = day1::parse(&input).unwrap();
+        prop_assert_eq!(
+            day1::similarity_score(&lists, Counting::Sparse),
+            day1::similarity_score(&lists, Counting::Dense)
+        );
+    }
+
+    #[test]
+    fn test_toleration_matches_brute_force(report in report()) {
+        prop_assert_eq!(
+            report.is_safe_with_toleration(),
+            is_safe_brute_force(&report),
+            "levels {:?}",
+            report.levels()
+        );
+    }
+
+    #[test]
+    fn test_part2_counts_tolerated_reports(reports in prop::collection::vec(report(), 0..20)) {
+        let expected = reports.iter().filter(|r| is_safe_brute_force(r)).count();
+        prop_assert_eq!(day2::part2(&reports), Ok(expected as u16));
+    }
+
+    #[test]
+    fn test_move_files_matches_brute_force(disk_map in disk_map()) {
+        let packed = day9::parse(&disk_map).unwrap();
+        prop_assert_eq!(day9::part2(&packed), Ok(compact_files_brute_force(&packed)));
+    }
+
+    #[test]
+    fn test_blink_with_frequencies_matches_blink(
+        stones in prop::collection::vec(0u64..1_000_000, 0..8),
+        iterations in 0u8..15,
+    ) {
+        let line = stones.iter().map(u64::to_string).collect::<Vec<_>>().join(" ");
+        let mut stones = Stones::from_line(&line).unwrap();
+        let mut frequencies = HashMap::new();
+        for stone in stones.stones() {
+            *frequencies.entry(*stone).or_insert(0) += 1;
+        }
+
+        for _ in 0..iterations {
+            stones.blink().unwrap();
+        }
+        prop_assert_eq!(
+            day11::blink_with_frequencies(frequencies, iterations),
+            Ok(stones.stones().len() as u64)
+        );
+    }
+}
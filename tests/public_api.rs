@@ -0,0 +1,64 @@
+//! Use the days the way other tools would, through the public library API
+use std::collections::HashMap;
+
+use aoc_rs_2024::{
+    day11::{self, Stones},
+    day3::InstructionParser,
+    day9::{self, MemoryMapOptimized},
+    solution::{self, Answer, Part, SOLUTIONS},
+};
+
+#[test]
+fn test_instruction_parser() {
+    let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+    let all: Vec<u32> = InstructionParser::new(input, false).collect();
+    assert_eq!(all, vec![8, 25, 88, 40]);
+    let enabled: Vec<u32> = InstructionParser::new(input, true).collect();
+    assert_eq!(enabled, vec![8, 40]);
+}
+
+#[test]
+fn test_memory_map_optimized() {
+    let packed = day9::parse("2333133121414131402").unwrap();
+    let files = MemoryMapOptimized::from_packed(&packed).move_files();
+    // 00992111777.44.333....5555.6666.....8888..
+    let index_of = |id| files.iter().find(|file| file.id == id).unwrap().index;
+    assert_eq!(index_of(0), 0);
+    assert_eq!(index_of(9), 2);
+    assert_eq!(index_of(2), 4);
+    assert_eq!(index_of(8), 36);
+}
+
+#[test]
+fn test_blink_with_frequencies_matches_blink() {
+    let mut stones = Stones::from_line("125 17").unwrap();
+    let mut frequencies = HashMap::new();
+    for stone in stones.stones() {
+        *frequencies.entry(*stone).or_insert(0) += 1;
+    }
+
+    for _ in 0..25 {
+        stones.blink().unwrap();
+    }
+    assert_eq!(
+        day11::blink_with_frequencies(frequencies, 25),
+        Ok(stones.stones().len() as u64)
+    );
+}
+
+#[test]
+fn test_registry() {
+    // scaffolding a new day adds to the registry, so only check the days that exist
+    for day in 1..=11 {
+        assert_eq!(
+            solution::find(day).map(|solution| solution.day()),
+            Some(day)
+        );
+    }
+    assert!(SOLUTIONS
+        .windows(2)
+        .all(|days| days[0].day() < days[1].day()));
+    let parsed = solution::find(11).unwrap().parse("125 17").unwrap();
+    assert_eq!(parsed.solve(Part::One), Ok(Answer::Integer(55312)));
+    assert!(solution::find(26).is_none());
+}